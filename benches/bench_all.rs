@@ -48,6 +48,58 @@ mod ef {
             }
         })
     }
+
+    #[bench]
+    fn select_loop(b: &mut Bencher) {
+        let (v, mut rng) = test_vector();
+        let ef = elias_fano_rust::EliasFano::from_vec(&v).unwrap();
+        let indices = (0..TRIALS).map(|_| rng.gen_range(0, SIZE)).collect::<Vec<_>>();
+        let mut result = vec![0; indices.len()];
+        b.iter(|| {
+            for (index, value) in indices.iter().zip(result.iter_mut()) {
+                *value = ef.unchecked_select(*index);
+            }
+            black_box(&result);
+        })
+    }
+
+    #[bench]
+    fn select_batch(b: &mut Bencher) {
+        let (v, mut rng) = test_vector();
+        let ef = elias_fano_rust::EliasFano::from_vec(&v).unwrap();
+        let indices = (0..TRIALS).map(|_| rng.gen_range(0, SIZE)).collect::<Vec<_>>();
+        let mut result = vec![0; indices.len()];
+        b.iter(|| {
+            ef.select_batch(&indices, &mut result);
+            black_box(&result);
+        })
+    }
+
+    #[bench]
+    fn rank_loop(b: &mut Bencher) {
+        let (v, mut rng) = test_vector();
+        let ef = elias_fano_rust::EliasFano::from_vec(&v).unwrap();
+        let values = (0..TRIALS).map(|_| rng.gen_range(0, MAX)).collect::<Vec<_>>();
+        let mut result = vec![0; values.len()];
+        b.iter(|| {
+            for (value, rank) in values.iter().zip(result.iter_mut()) {
+                *rank = ef.unchecked_rank(*value);
+            }
+            black_box(&result);
+        })
+    }
+
+    #[bench]
+    fn rank_batch(b: &mut Bencher) {
+        let (v, mut rng) = test_vector();
+        let ef = elias_fano_rust::EliasFano::from_vec(&v).unwrap();
+        let values = (0..TRIALS).map(|_| rng.gen_range(0, MAX)).collect::<Vec<_>>();
+        let mut result = vec![0; values.len()];
+        b.iter(|| {
+            ef.rank_batch(&values, &mut result);
+            black_box(&result);
+        })
+    }
 }
 
 
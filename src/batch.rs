@@ -0,0 +1,298 @@
+use super::*;
+
+/// Number of queries whose lookups are pipelined together by the batch
+/// methods. It must be big enough to hide the latency of the memory, but
+/// small enough that the prefetched lines are not evicted before their use.
+const BATCH_SIZE: usize = 16;
+
+/// In the sorted batches, the start of the bucket of the next query is found
+/// by scanning the high-bits if it's at most this number of zeros ahead,
+/// otherwise with a `select0`, which on average scans half a quantum of
+/// zeros from its sample.
+const SCAN_THRESHOLD: u64 = 1 << (INDEX_SHIFT - 1);
+
+impl<T: UnsignedInteger> EliasFano<T> {
+    /// Write in `result` the value of each of the `indices`, like calling
+    /// `unchecked_select` on each of them.
+    ///
+    /// On big structures every select stalls on the cache misses of the
+    /// index of the ones, of the high-bits and of the low-bits. Here the
+    /// queries are processed in groups and, for each group, first the index
+    /// samples and the low-bits are prefetched, then the words of the
+    /// high-bits where the scans start and only then the values are decoded,
+    /// so that the loads of different queries overlap.
+    ///
+    /// # Panics
+    /// If `indices` and `result` have different lengths.
+    ///
+    /// ```rust
+    /// # use elias_fano_rust::EliasFano;
+    /// let ef = EliasFano::from_vec(&[5_u64, 8, 8, 15, 32]).unwrap();
+    /// let mut result = vec![0; 3];
+    /// ef.select_batch(&[4, 0, 2], &mut result);
+    /// assert_eq!(result, vec![32, 5, 8]);
+    /// ```
+    pub fn select_batch(&self, indices: &[u64], result: &mut [T]) {
+        assert_eq!(
+            indices.len(), result.len(),
+            "The indices and the result of select_batch must have the same length."
+        );
+        let index_ones = &self.high_bits.high_bits_index_ones;
+        for (indices, result) in indices.chunks(BATCH_SIZE).zip(result.chunks_mut(BATCH_SIZE)) {
+            // load the index samples and the low-bits
+            for index in indices {
+                prefetch(index_ones, (index >> INDEX_SHIFT) as usize);
+                prefetch(&self.low_bits, ((index * self.low_bit_count) >> WORD_SHIFT) as usize);
+            }
+            // load the words of the high-bits where the scans start
+            for index in indices {
+                let bit_pos = index_ones[(index >> INDEX_SHIFT) as usize];
+                prefetch(&self.high_bits.high_bits, (bit_pos >> WORD_SHIFT) as usize);
+            }
+            for (index, value) in indices.iter().zip(result.iter_mut()) {
+                *value = self.unchecked_select(*index);
+            }
+        }
+    }
+
+    /// Write in `result` the number of elements smaller than each of the
+    /// `values`, like calling `unchecked_rank` on each of them.
+    ///
+    /// The lookups are pipelined as in `select_batch`: the samples of the
+    /// index of the zeros, then the words of the high-bits and then the
+    /// low-bits of the bucket of each value are prefetched before the scans.
+    /// In strict mode the rank is a binary search over selects, so the
+    /// queries are answered one by one.
+    ///
+    /// # Panics
+    /// If `values` and `result` have different lengths.
+    ///
+    /// ```rust
+    /// # use elias_fano_rust::EliasFano;
+    /// let ef = EliasFano::from_vec(&[5_u64, 8, 8, 15, 32]).unwrap();
+    /// let mut result = vec![0; 4];
+    /// ef.rank_batch(&[15, 8, 17, 100], &mut result);
+    /// assert_eq!(result, vec![3, 1, 4, 5]);
+    /// ```
+    pub fn rank_batch(&self, values: &[T], result: &mut [u64]) {
+        assert_eq!(
+            values.len(), result.len(),
+            "The values and the result of rank_batch must have the same length."
+        );
+        if self.strict || self.is_empty() {
+            for (value, rank) in values.iter().zip(result.iter_mut()) {
+                *rank = self.unchecked_rank(*value);
+            }
+            return;
+        }
+
+        let index_zeros = &self.high_bits.high_bits_index_zeros;
+        let mut positions = [0_u64; BATCH_SIZE];
+        for (values, result) in values.chunks(BATCH_SIZE).zip(result.chunks_mut(BATCH_SIZE)) {
+            // load the index samples of the buckets
+            for value in values {
+                if let Some(high) = self.bucket_to_select(*value) {
+                    prefetch(index_zeros, ((high - 1) >> INDEX_SHIFT) as usize);
+                }
+            }
+            // load the words of the high-bits where the select0 starts
+            for value in values {
+                if let Some(high) = self.bucket_to_select(*value) {
+                    let bit_pos = index_zeros[((high - 1) >> INDEX_SHIFT) as usize];
+                    prefetch(&self.high_bits.high_bits, (bit_pos >> WORD_SHIFT) as usize);
+                }
+            }
+            // find the start of the buckets and load their low-bits
+            for (value, position) in values.iter().zip(positions.iter_mut()) {
+                if *value > self.last_value || *value <= self.min_value {
+                    continue;
+                }
+                let high = self.extract_high_bits(*value - self.min_value);
+                *position = match self.bucket_to_select(*value) {
+                    Some(high) => self.high_bits.unchecked_select0(high - 1) + 1,
+                    None => 0,
+                };
+                let ones = *position - high;
+                prefetch(&self.low_bits, ((ones * self.low_bit_count) >> WORD_SHIFT) as usize);
+            }
+            // scan the buckets
+            for ((value, position), rank) in values.iter().zip(positions.iter()).zip(result.iter_mut()) {
+                if *value > self.last_value {
+                    *rank = self.current_number_of_elements;
+                    continue;
+                }
+                if *value <= self.min_value {
+                    *rank = 0;
+                    continue;
+                }
+                let (high, low) = self.extract_high_low_bits(*value - self.min_value);
+                let mut index = *position;
+                let mut ones = index - high;
+                while self.high_bits.unchecked_get(index) && self.read_lowbits(ones) < low {
+                    ones += 1;
+                    index += 1;
+                }
+                *rank = ones;
+            }
+        }
+    }
+
+    /// Write in `result` the number of elements smaller than each of the
+    /// `values`, like calling `unchecked_rank` on each of them.
+    ///
+    /// The values should be sorted: the structure is then walked once, each
+    /// query starting from the position of the previous one and reaching its
+    /// bucket either by scanning the high-bits, if it's close, or by skipping
+    /// with a select. The results are still right if the values are not
+    /// sorted, but every decreasing query restarts from the beginning.
+    ///
+    /// # Panics
+    /// If `values` and `result` have different lengths.
+    ///
+    /// ```rust
+    /// # use elias_fano_rust::EliasFano;
+    /// let ef = EliasFano::from_vec(&[5_u64, 8, 8, 15, 32]).unwrap();
+    /// let mut result = vec![0; 5];
+    /// ef.rank_sorted_batch(&[0, 8, 9, 15, 100], &mut result);
+    /// assert_eq!(result, vec![0, 1, 3, 3, 5]);
+    /// ```
+    pub fn rank_sorted_batch(&self, values: &[T], result: &mut [u64]) {
+        assert_eq!(
+            values.len(), result.len(),
+            "The values and the result of rank_sorted_batch must have the same length."
+        );
+        self.sorted_batch(values, |i, rank, _| result[i] = rank);
+    }
+
+    /// Write in `result` if each of the `values` is in the set, like calling
+    /// `contains` on each of them. As in `rank_sorted_batch`, the values
+    /// should be sorted so that the structure is walked once.
+    ///
+    /// # Panics
+    /// If `values` and `result` have different lengths.
+    ///
+    /// ```rust
+    /// # use elias_fano_rust::EliasFano;
+    /// let ef = EliasFano::from_vec(&[5_u64, 8, 8, 15, 32]).unwrap();
+    /// let mut result = vec![false; 5];
+    /// ef.contains_sorted_batch(&[0, 8, 9, 15, 100], &mut result);
+    /// assert_eq!(result, vec![false, true, false, true, false]);
+    /// ```
+    pub fn contains_sorted_batch(&self, values: &[T], result: &mut [bool]) {
+        assert_eq!(
+            values.len(), result.len(),
+            "The values and the result of contains_sorted_batch must have the same length."
+        );
+        self.sorted_batch(values, |i, _, found| result[i] = found);
+    }
+
+    /// Call `emit` with the index, the rank and the membership of each of
+    /// the values, walking the structure in order.
+    fn sorted_batch(&self, values: &[T], mut emit: impl FnMut(usize, u64, bool)) {
+        if self.is_empty() {
+            for i in 0..values.len() {
+                emit(i, 0, false);
+            }
+            return;
+        }
+
+        // the rank of the previous query, and in non-strict mode the
+        // position in the high-bits of the element with that rank
+        let (mut ones, mut position) = (0, 0);
+        let mut previous = None;
+        for (i, value) in values.iter().enumerate() {
+            if previous.map(|previous| *value < previous).unwrap_or(false) {
+                ones = 0;
+                position = 0;
+            }
+            previous = Some(*value);
+
+            if *value < self.min_value {
+                emit(i, 0, false);
+                continue;
+            }
+            if *value > self.last_value {
+                emit(i, self.current_number_of_elements, false);
+                continue;
+            }
+
+            if self.strict {
+                // the encoded values do not follow the buckets of the values,
+                // so gallop over the selects from the previous rank
+                let mut step = 1;
+                let mut end = ones;
+                while end < self.current_number_of_elements && self.unchecked_select(end) < *value {
+                    ones = end + 1;
+                    end += step;
+                    step <<= 1;
+                }
+                let mut end = end.min(self.current_number_of_elements);
+                while ones < end {
+                    let middle = ones + (end - ones) / 2;
+                    if self.unchecked_select(middle) < *value {
+                        ones = middle + 1;
+                    } else {
+                        end = middle;
+                    }
+                }
+                let found = ones < self.current_number_of_elements
+                    && self.unchecked_select(ones) == *value;
+                emit(i, ones, found);
+                continue;
+            }
+
+            let (high, low) = self.extract_high_low_bits(*value - self.min_value);
+            // the number of zeros before the position is its bucket
+            let bucket = position - ones;
+            if high > bucket {
+                position = match high - bucket <= SCAN_THRESHOLD {
+                    true => self.skip_zeros(position, high - bucket),
+                    false => self.high_bits.unchecked_select0(high - 1) + 1,
+                };
+                ones = position - high;
+            }
+            while self.high_bits.unchecked_get(position) && self.read_lowbits(ones) < low {
+                ones += 1;
+                position += 1;
+            }
+            let found = self.high_bits.unchecked_get(position) && self.read_lowbits(ones) == low;
+            emit(i, ones, found);
+        }
+    }
+
+    /// Return the position in the high-bits right after the `zeros`-th zero
+    /// starting from `position`, scanning the words.
+    #[inline]
+    fn skip_zeros(&self, position: u64, mut zeros: u64) -> u64 {
+        let mut word_index = position >> WORD_SHIFT;
+        // flip the word so that the zeros are the ones to count
+        let mut code = !self.high_bits.high_bits[word_index as usize] & (u64::MAX << (position & WORD_MASK));
+        loop {
+            let popcnt = code.count_ones() as u64;
+            if popcnt >= zeros {
+                break;
+            }
+            zeros -= popcnt;
+            word_index += 1;
+            code = !self.high_bits.high_bits[word_index as usize];
+        }
+        // clean the zeros before the wanted one
+        for _ in 1..zeros {
+            code &= code - 1;
+        }
+        (word_index << WORD_SHIFT) + code.trailing_zeros() as u64 + 1
+    }
+
+    /// Return the high-bits of the given value if the non-strict rank
+    /// needs a select0 to find the start of its bucket.
+    #[inline]
+    fn bucket_to_select(&self, value: T) -> Option<u64> {
+        if value > self.last_value || value <= self.min_value {
+            return None;
+        }
+        match self.extract_high_bits(value - self.min_value) {
+            0 => None,
+            high => Some(high),
+        }
+    }
+}
@@ -1,76 +1,259 @@
 use super::*;
 
-impl EliasFano {
+/// Compute the number of bits needed for the low parts of the values
+/// as `floor(log2(universe / number_of_elements))`.
+pub(crate) fn compute_low_bit_count<T: UnsignedInteger>(universe: T, number_of_elements: usize) -> Result<u64, String> {
+    // Compute the size of the low bits.
+    let low_bit_count = if number_of_elements > 0 && universe.to_u128() >= number_of_elements as u128 {
+        // floor(log2(universe / n)) with integers so that it works without std
+        127 - (universe.to_u128() / number_of_elements as u128).leading_zeros() as u64
+    } else {
+        0
+    };
 
+    // saturate at the max we can handle
+    if low_bit_count > T::BITS {
+        return Err(format!(concat!(
+                "The lowbits are too big, we only support {} bits for the low parts.",
+                "The value were universe {} number_of_elements {}"
+            ),
+            T::BITS, universe, number_of_elements
+        ));
+    }
+
+    Ok(low_bit_count)
+}
+
+impl<T: UnsignedInteger> EliasFano<T> {
+
+    #[inline]
+    pub fn new(universe: T, number_of_elements: usize) -> Result<EliasFano<T>, String> {
+        EliasFano::new_with_min_value(T::ZERO, universe, number_of_elements)
+    }
+
+    /// Create an empty elias-fano for values in the range [`min_value`, `universe`].
+    ///
+    /// The values are encoded as `value - min_value` so the space used
+    /// only depends on the size of the range and not on its position.
+    /// All the methods still take and return the original values.
+    ///
+    /// # Arguments
+    ///
+    /// * min_value: T - The minimum value that will be pushed.
+    /// * universe: T - The maximum value that will be pushed.
+    /// * number_of_elements: usize - The number of values that will be pushed.
+    ///
+    /// ```
+    /// # use elias_fano_rust::EliasFano;
+    /// let mut ef = EliasFano::new_with_min_value(1_600_000_000_u64, 1_700_000_000, 3).unwrap();
+    /// ef.push(1_600_000_001).unwrap();
+    /// ef.push(1_650_000_000).unwrap();
+    /// ef.push(1_700_000_000).unwrap();
+    /// assert_eq!(ef.select(1).unwrap(), 1_650_000_000);
+    /// assert_eq!(ef.rank(1_700_000_000), Some(2));
+    /// ```
     #[inline]
-    pub fn new(universe: u64, number_of_elements: usize) -> Result<EliasFano, String> {
+    pub fn new_with_min_value(min_value: T, universe: T, number_of_elements: usize) -> Result<EliasFano<T>, String> {
+        EliasFano::new_with_mode(min_value, universe, number_of_elements, false)
+    }
+
+    /// Create an empty elias-fano for **strictly increasing** values
+    /// in the range [`min_value`, `universe`].
+    ///
+    /// Since there are no duplicates, the values are encoded as
+    /// `value - min_value - index` which reduces the universe by the number
+    /// of elements. The push checks that the values are strictly increasing
+    /// and all the methods still take and return the original values.
+    ///
+    /// The encoded values do not give the position of a value in the high-bits,
+    /// so `rank`, `unchecked_rank`, `contains` and the queries built on them
+    /// do a binary search, which takes O(log n) selects instead of the
+    /// constant time of the non-strict mode.
+    ///
+    /// # Arguments
+    ///
+    /// * min_value: T - The minimum value that will be pushed.
+    /// * universe: T - The maximum value that will be pushed.
+    /// * number_of_elements: usize - The number of values that will be pushed.
+    ///
+    /// ```
+    /// # use elias_fano_rust::EliasFano;
+    /// let mut ef = EliasFano::new_strict(0_u64, 10, 3).unwrap();
+    /// ef.push(2).unwrap();
+    /// assert!(ef.push(2).is_err());
+    /// ef.push(3).unwrap();
+    /// ef.push(10).unwrap();
+    /// assert_eq!(ef.iter().collect::<Vec<_>>(), vec![2, 3, 10]);
+    /// assert_eq!(ef.rank(10), Some(2));
+    /// ```
+    #[inline]
+    pub fn new_strict(min_value: T, universe: T, number_of_elements: usize) -> Result<EliasFano<T>, String> {
+        EliasFano::new_with_mode(min_value, universe, number_of_elements, true)
+    }
+
+    #[inline]
+    fn new_with_mode(min_value: T, universe: T, number_of_elements: usize, strict: bool) -> Result<EliasFano<T>, String> {
+        if min_value > universe {
+            return Err(format!(
+                "The minimum value {} is bigger than the universe {}.",
+                min_value, universe
+            ));
+        }
         if number_of_elements == 0 {
             return Ok(EliasFano{
                 universe: universe,
+                min_value,
+                strict,
                 low_bit_count: 0,
-                low_bit_mask:  0,
+                low_bit_mask:  T::ZERO,
                 number_of_elements: 0,
                 high_bits: SimpleSelect::new(),
                 low_bits: vec![],
                 last_high_value: 0,
-                last_value: 0,
+                last_value: T::ZERO,
                 last_index: 0,
                 current_number_of_elements: 0,
             });
         }
-        // Compute the size of the low bits.
-        let low_bit_count = if universe >= number_of_elements as u64 {
-            (universe as f64 / number_of_elements as f64).log2().floor() as u64
-        } else {
-            0
-        };
-
-        // saturate at the max we can handle
-        if low_bit_count > 64 {
-            return Err(format!(concat!(
-                    "The lowbits are too big, we only support 64 bits for the low parts.",
-                    "The value were universe {} number_of_elements {}"
-                ),
-                universe, number_of_elements
-            ));
+        let mut encoded_universe = universe - min_value;
+        if strict {
+            if encoded_universe.to_u128() < (number_of_elements - 1) as u128 {
+                return Err(format!(
+                    concat!(
+                        "There cannot be {} strictly increasing values ",
+                        "between the minimum value {} and the universe {}."
+                    ),
+                    number_of_elements, min_value, universe
+                ));
+            }
+            encoded_universe = encoded_universe - T::from_u64(number_of_elements as u64 - 1);
         }
+        let low_bit_count = compute_low_bit_count(encoded_universe, number_of_elements)?;
 
         // add 2 to do the ceil and have brenchless primitives.
         let low_size = get_vec_size(low_bit_count, number_of_elements);
 
         Ok(EliasFano {
             universe,
+            min_value,
+            strict,
             low_bit_count,
             // Pre-rendered mask to execute a fast version of the mod operation.
-            low_bit_mask: shr(0xffffffffffffffff, 64 - low_bit_count),
+            low_bit_mask: T::mask(low_bit_count),
             high_bits: SimpleSelect::with_capacity(2 * number_of_elements),
             number_of_elements: number_of_elements as u64,
             low_bits: vec![0; low_size as usize],
             last_high_value: 0,
-            last_value: 0,
+            last_value: T::ZERO,
             last_index: 0,
             current_number_of_elements: 0,
         })
     }
 
+    /// Create an elias-fano from its parts, for example to load it from a
+    /// custom format. All the elements are considered pushed, so the number
+    /// of elements is the number of ones in the high-bits.
+    ///
+    /// # Arguments
+    ///
+    /// * low_bits: Vec<u64> - The packed low parts of the encoded values.
+    /// * high_bits: SimpleSelect - The high parts of the encoded values in unary code.
+    /// * universe: T - The maximum value that can be stored.
+    /// * min_value: T - The minimum value that can be stored.
+    /// * strict: bool - If the values are encoded as `value - min_value - index`.
+    /// * number_of_elements: usize - The capacity of the elias-fano.
+    /// * low_bit_count: u64 - The number of bits of the low parts.
+    ///
+    /// # Safety
+    /// The parts must satisfy all the invariants checked by `validate`,
+    /// which can be called on the result to check them.
+    /// Otherwise the methods can read out of bounds, which is undefined
+    /// behaviour with the `unsafe` feature.
+    ///
+    /// ```
+    /// # use elias_fano_rust::EliasFano;
+    /// let ef = EliasFano::from_vec(&[5_u64, 8, 8, 15, 32]).unwrap();
+    /// let copy = unsafe {
+    ///     EliasFano::from_raw_parts(
+    ///         ef.low_bits().to_vec(), ef.high_bits().clone(), ef.get_universe(),
+    ///         ef.get_min_value(), ef.is_strict(), ef.capacity(), ef.low_bit_count(),
+    ///     )
+    /// };
+    /// assert!(copy.validate().is_valid());
+    /// assert_eq!(copy, ef);
+    /// ```
+    pub unsafe fn from_raw_parts(
+        low_bits: Vec<u64>,
+        high_bits: SimpleSelect,
+        universe: T,
+        min_value: T,
+        strict: bool,
+        number_of_elements: usize,
+        low_bit_count: u64,
+    ) -> EliasFano<T> {
+        let current_number_of_elements = high_bits.count_ones();
+        let mut result = EliasFano{
+            low_bits,
+            high_bits,
+            universe,
+            min_value,
+            strict,
+            number_of_elements: number_of_elements as u64,
+            low_bit_count,
+            low_bit_mask: match low_bit_count <= T::BITS {
+                true => T::mask(low_bit_count),
+                false => T::ZERO,
+            },
+            last_high_value: 0,
+            last_value: T::ZERO,
+            last_index: current_number_of_elements,
+            current_number_of_elements,
+        };
+
+        // decode the last value without trusting the parts, so that
+        // invalid parts are reported by `validate` instead of panicking
+        let last_one = result.high_bits.high_bits.iter().enumerate().rev()
+            .find(|(_, word)| **word != 0)
+            .map(|(i, word)| ((i as u64) << WORD_SHIFT) + WORD_MASK - word.leading_zeros() as u64);
+        if let (Some(last_one), true) = (last_one, current_number_of_elements > 0) {
+            let index = current_number_of_elements - 1;
+            result.last_high_value = last_one.saturating_sub(index);
+            let low_words = get_vec_size(low_bit_count, current_number_of_elements as usize);
+            if low_bit_count <= T::BITS && result.low_bits.len() as u64 >= low_words
+                && result.last_high_value.leading_zeros() as u64 + T::BITS >= WORD_SIZE + low_bit_count
+            {
+                let value = (result.last_high_value as u128).checked_shl(low_bit_count as u32).unwrap_or(0)
+                    | result.read_lowbits(index).to_u128();
+                let value = value.checked_add(min_value.to_u128())
+                    .and_then(|value| value.checked_add(if strict { index as u128 } else { 0 }));
+                if let Some(value) = value.filter(|value| *value <= T::MAX.to_u128()) {
+                    result.last_value = T::from_u128(value);
+                }
+            }
+        }
+
+        result
+    }
+
     /// Create a new elias-fano from an iterable of **sorted values**.
     ///    low_bits: Vec<u64>,
 
     /// # Arguments
     ///
-    /// * values: &[u64] - Vector of sorted integers to encode.
-    /// * max: u64 - The maximum value within the vector.
+    /// * values: impl Iterator<Item = T> - Iterator of sorted integers to encode.
+    /// * universe: T - The maximum value within the vector.
+    /// * number_of_elements: usize - The number of values to encode.
     /// ```
     /// # use elias_fano_rust::EliasFano;
-    /// let vector = [5, 8, 8, 15, 32];
+    /// let vector = [5_u64, 8, 8, 15, 32];
     /// let ef = EliasFano::from_iter(vector.iter().cloned(), *vector.last().unwrap(), vector.len()).unwrap();
     /// ```
     #[inline]
     pub fn from_iter(
-        values: impl Iterator<Item = u64>,
-        universe: u64,
+        values: impl Iterator<Item = T>,
+        universe: T,
         number_of_elements: usize,
-    ) -> Result<EliasFano, String> {
+    ) -> Result<EliasFano<T>, String> {
         let mut result = EliasFano::new(universe, number_of_elements)?;
 
         result.build_low_high_bits(values)?;
@@ -78,34 +261,136 @@ impl EliasFano {
         Ok(result)
     }
 
+    /// Create a new elias-fano from an iterable of **sorted values**
+    /// which are all in the range [`min_value`, `universe`].
+    ///
+    /// # Arguments
+    ///
+    /// * values: impl Iterator<Item = T> - Iterator of sorted integers to encode.
+    /// * min_value: T - The minimum value within the vector.
+    /// * universe: T - The maximum value within the vector.
+    /// * number_of_elements: usize - The number of values to encode.
+    #[inline]
+    pub fn from_iter_with_min_value(
+        values: impl Iterator<Item = T>,
+        min_value: T,
+        universe: T,
+        number_of_elements: usize,
+    ) -> Result<EliasFano<T>, String> {
+        let mut result = EliasFano::new_with_min_value(min_value, universe, number_of_elements)?;
+
+        result.build_low_high_bits(values)?;
+
+        Ok(result)
+    }
+
     /// Create a new elias-fano from a vector of **sorted values**.
     ///
     /// # Arguments
     ///
-    /// * values: &[u64] - Vector of sorted integers to encode.
-    /// * max: u64 - The maximum value within the vector.
+    /// * values: &[T] - Vector of sorted integers to encode.
     ///
     /// ```
     /// # use elias_fano_rust::EliasFano;
-    /// let vector = [5, 8, 8, 15, 32];
+    /// let vector = [5_u64, 8, 8, 15, 32];
     /// let ef = EliasFano::from_vec(&vector).unwrap();
     /// ```
     #[inline]
-    pub fn from_vec(values: &[u64]) -> Result<EliasFano, String> {
+    pub fn from_vec(values: &[T]) -> Result<EliasFano<T>, String> {
         EliasFano::from_iter(
             values.iter().cloned(),
-            *values.last().unwrap_or(&0),
+            *values.last().unwrap_or(&T::ZERO),
+            values.len(),
+        )
+    }
+
+    /// Create a new elias-fano from a vector of **sorted values**, using
+    /// the first value as the minimum value, so the encoding only depends
+    /// on the difference between the last and the first value.
+    ///
+    /// # Arguments
+    ///
+    /// * values: &[T] - Vector of sorted integers to encode.
+    ///
+    /// ```
+    /// # use elias_fano_rust::EliasFano;
+    /// let vector = [1_600_000_005_u64, 1_600_000_008, 1_600_000_015];
+    /// let ef = EliasFano::from_vec_auto_min(&vector).unwrap();
+    /// assert_eq!(ef.get_min_value(), 1_600_000_005);
+    /// assert!(ef.size() < EliasFano::from_vec(&vector).unwrap().size());
+    /// ```
+    #[inline]
+    pub fn from_vec_auto_min(values: &[T]) -> Result<EliasFano<T>, String> {
+        EliasFano::from_iter_with_min_value(
+            values.iter().cloned(),
+            *values.first().unwrap_or(&T::ZERO),
+            *values.last().unwrap_or(&T::ZERO),
             values.len(),
         )
     }
 
+    /// Create a new strict elias-fano from an iterable of **strictly increasing values**
+    /// which are all in the range [`min_value`, `universe`].
+    ///
+    /// The ranks take O(log n) in strict mode, see `new_strict`.
+    ///
+    /// # Arguments
+    ///
+    /// * values: impl Iterator<Item = T> - Iterator of strictly increasing integers to encode.
+    /// * min_value: T - The minimum value within the vector.
+    /// * universe: T - The maximum value within the vector.
+    /// * number_of_elements: usize - The number of values to encode.
     #[inline]
-    pub fn unchecked_push(&mut self, value: u64) {
+    pub fn from_iter_strict(
+        values: impl Iterator<Item = T>,
+        min_value: T,
+        universe: T,
+        number_of_elements: usize,
+    ) -> Result<EliasFano<T>, String> {
+        let mut result = EliasFano::new_strict(min_value, universe, number_of_elements)?;
+
+        result.build_low_high_bits(values)?;
+
+        Ok(result)
+    }
+
+    /// Create a new strict elias-fano from a vector of **strictly increasing values**.
+    ///
+    /// The ranks take O(log n) in strict mode, see `new_strict`.
+    ///
+    /// # Arguments
+    ///
+    /// * values: &[T] - Vector of strictly increasing integers to encode.
+    ///
+    /// ```
+    /// # use elias_fano_rust::EliasFano;
+    /// let vector = [5_u64, 8, 15, 32];
+    /// let ef = EliasFano::from_vec_strict(&vector).unwrap();
+    /// assert!(EliasFano::from_vec_strict(&[5_u64, 8, 8, 15, 32]).is_err());
+    /// ```
+    #[inline]
+    pub fn from_vec_strict(values: &[T]) -> Result<EliasFano<T>, String> {
+        EliasFano::from_iter_strict(
+            values.iter().cloned(),
+            *values.first().unwrap_or(&T::ZERO),
+            *values.last().unwrap_or(&T::ZERO),
+            values.len(),
+        )
+    }
+
+    #[inline]
+    pub fn unchecked_push(&mut self, value: T) {
         self.last_value = value;
         self.current_number_of_elements += 1;
 
+        // remove the offset and, in strict mode, the index
+        let mut encoded_value = value - self.min_value;
+        if self.strict {
+            encoded_value = encoded_value - T::from_u64(self.last_index);
+        }
+
         // split into high and low bits
-        let (high, low) = self.extract_high_low_bits(value);
+        let (high, low) = self.extract_high_low_bits(encoded_value);
 
         // The following for loop and push
         // are used to encode in inverted unary code for the high bits
@@ -115,17 +400,29 @@ impl EliasFano {
         }
         self.high_bits.push(true);
 
-        #[cfg(not(feature = "unsafe"))]
-        safe_write(&mut self.low_bits, self.last_index, low, self.low_bit_count);
-        #[cfg(feature = "unsafe")]
-        unsafe_write(&mut self.low_bits, self.last_index, low, self.low_bit_count);
+        T::write_packed(&mut self.low_bits, self.last_index, low, self.low_bit_count);
 
         self.last_high_value = high;
         self.last_index += 1;
     }
 
     #[inline]
-    pub fn push(&mut self, value: u64) -> Result<(), String> {
+    pub fn push(&mut self, value: T) -> Result<(), String> {
+        if value < self.min_value {
+            return Err(format!(
+                "The given value {} is smaller than the minimum value {}.",
+                value, self.min_value
+            ));
+        }
+        if self.strict && self.current_number_of_elements > 0 && self.last_value >= value {
+            return Err(format!(
+                concat!(
+                    "Cannot initialize a strict Elias-Fano from values which are ",
+                    "not strictly increasing! Previous value was {} but given value is {}.",
+                ),
+                self.last_value, value
+            ));
+        }
         if self.last_value > value {
             return Err(format!(
                 concat!(
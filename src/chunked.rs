@@ -0,0 +1,438 @@
+use super::*;
+
+/// Number of low bits of the values stored in each container.
+const CHUNK_SHIFT: u64 = 16;
+/// Number of values in the universe of each container.
+const CHUNK_SIZE: u64 = 1 << CHUNK_SHIFT;
+/// Mask to extract the low bits of a value.
+const CHUNK_MASK: u64 = CHUNK_SIZE - 1;
+/// Number of words of the bitmap of a container.
+const CHUNK_WORDS: usize = (CHUNK_SIZE >> WORD_SHIFT) as usize;
+
+#[derive(Clone, Debug, PartialEq)]
+/// The set of the low 16 bits of the values of a chunk.
+pub enum ChunkContainer {
+    /// Sorted vector of the values, good for very small chunks.
+    Array(Vec<u16>),
+    /// Bitmap of the whole chunk, good for dense chunks.
+    Bitmap(SimpleSelect),
+    /// Strict EliasFano of the values, good for sparse chunks.
+    EliasFano(EliasFano<u16>),
+}
+
+impl ChunkContainer {
+    /// Build the container with the smallest estimated size for the given
+    /// **strictly increasing** values, which must not be empty.
+    pub fn from_sorted(values: &[u16]) -> Result<ChunkContainer, String> {
+        if values.is_empty() {
+            return Err("A container needs at least one value.".to_string());
+        }
+        if let Some(pair) = values.windows(2).find(|pair| pair[0] >= pair[1]) {
+            return Err(format!(
+                "The values of a container must be strictly increasing, but {} is followed by {}.",
+                pair[0], pair[1]
+            ));
+        }
+        let n = values.len();
+        let (first, last) = (values[0], values[n - 1]);
+        let array_size = core::mem::size_of_val(values) + core::mem::size_of::<Vec<u16>>();
+        let bitmap_size = SimpleSelect::estimate_size(CHUNK_SIZE, n as u64);
+        let elias_fano_size = EliasFano::<u16>::estimate_size(last - first - (n - 1) as u16, n);
+
+        if array_size <= bitmap_size && array_size <= elias_fano_size {
+            let mut values = values.to_vec();
+            values.shrink_to_fit();
+            Ok(ChunkContainer::Array(values))
+        } else if bitmap_size <= elias_fano_size {
+            let mut words = vec![0_u64; CHUNK_WORDS];
+            for value in values {
+                words[(*value as u64 >> WORD_SHIFT) as usize] |= 1 << (*value as u64 & WORD_MASK);
+            }
+            Ok(ChunkContainer::Bitmap(SimpleSelect::from_vec(words)))
+        } else {
+            let mut ef = EliasFano::from_vec_strict(values)?;
+            ef.shrink_to_fit();
+            Ok(ChunkContainer::EliasFano(ef))
+        }
+    }
+
+    /// Build the container with the smallest estimated size from a bitmap
+    /// of the chunk, return None if the bitmap is empty.
+    fn from_words(words: Vec<u64>) -> Option<ChunkContainer> {
+        let n: u64 = words.iter().map(|word| word.count_ones() as u64).sum();
+        if n == 0 {
+            return None;
+        }
+        let mut values = Vec::with_capacity(n as usize);
+        for (i, mut word) in words.into_iter().enumerate() {
+            while word != 0 {
+                values.push(((i as u64) << WORD_SHIFT | word.trailing_zeros() as u64) as u16);
+                word &= word - 1;
+            }
+        }
+        ChunkContainer::from_sorted(&values).ok()
+    }
+
+    /// Return the number of values in the container.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        match self {
+            ChunkContainer::Array(values) => values.len() as u64,
+            ChunkContainer::Bitmap(bitmap) => bitmap.count_ones(),
+            ChunkContainer::EliasFano(ef) => ef.len() as u64,
+        }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub fn contains(&self, value: u16) -> bool {
+        match self {
+            ChunkContainer::Array(values) => values.binary_search(&value).is_ok(),
+            ChunkContainer::Bitmap(bitmap) => bitmap.unchecked_get(value as u64),
+            ChunkContainer::EliasFano(ef) => ef.contains(value),
+        }
+    }
+
+    /// Return the number of values in the container which are smaller than `value`.
+    #[inline]
+    pub fn rank(&self, value: u16) -> u64 {
+        match self {
+            ChunkContainer::Array(values) => values.partition_point(|x| *x < value) as u64,
+            ChunkContainer::Bitmap(bitmap) => bitmap.rank1(value as u64),
+            ChunkContainer::EliasFano(ef) => ef.unchecked_rank(value),
+        }
+    }
+
+    /// Return the value of the chosen index without checking the bounds.
+    #[inline]
+    pub fn unchecked_select(&self, index: u64) -> u16 {
+        match self {
+            ChunkContainer::Array(values) => values[index as usize],
+            ChunkContainer::Bitmap(bitmap) => bitmap.unchecked_select1(index) as u16,
+            ChunkContainer::EliasFano(ef) => ef.unchecked_select(index),
+        }
+    }
+
+    /// Return an iterator over the values in the container.
+    pub fn iter(&self) -> Box<dyn Iterator<Item = u16> + '_> {
+        match self {
+            ChunkContainer::Array(values) => Box::new(values.iter().cloned()),
+            ChunkContainer::Bitmap(bitmap) => Box::new(bitmap.iter().map(|value| value as u16)),
+            ChunkContainer::EliasFano(ef) => Box::new(ef.iter()),
+        }
+    }
+
+    /// Return the memory used in bytes
+    pub fn size(&self) -> usize {
+        match self {
+            ChunkContainer::Array(values) => {
+                values.capacity() * core::mem::size_of::<u16>() + core::mem::size_of::<Vec<u16>>()
+            }
+            ChunkContainer::Bitmap(bitmap) => bitmap.size().total(),
+            ChunkContainer::EliasFano(ef) => ef.size(),
+        }
+    }
+
+    /// Return the values which are in both the containers, if any.
+    pub fn intersection(&self, other: &ChunkContainer) -> Option<ChunkContainer> {
+        if let (ChunkContainer::Bitmap(a), ChunkContainer::Bitmap(b)) = (self, other) {
+            return ChunkContainer::from_words(
+                a.high_bits.iter().zip(b.high_bits.iter()).map(|(a, b)| a & b).collect()
+            );
+        }
+        // scan the smaller container and probe the bigger one
+        let (small, big) = match self.len() <= other.len() {
+            true => (self, other),
+            false => (other, self),
+        };
+        let values = small.iter().filter(|value| big.contains(*value)).collect::<Vec<_>>();
+        match values.is_empty() {
+            true => None,
+            false => ChunkContainer::from_sorted(&values).ok(),
+        }
+    }
+
+    /// Return the values which are in at least one of the containers.
+    pub fn union(&self, other: &ChunkContainer) -> ChunkContainer {
+        if let (ChunkContainer::Bitmap(a), ChunkContainer::Bitmap(b)) = (self, other) {
+            return ChunkContainer::from_words(
+                a.high_bits.iter().zip(b.high_bits.iter()).map(|(a, b)| a | b).collect()
+            ).unwrap();
+        }
+        let mut values = Vec::with_capacity((self.len() + other.len()) as usize);
+        let (mut a, mut b) = (self.iter().peekable(), other.iter().peekable());
+        loop {
+            let value = match (a.peek(), b.peek()) {
+                (Some(x), Some(y)) if x < y => a.next(),
+                (Some(x), Some(y)) if x > y => b.next(),
+                (Some(_), Some(_)) => {
+                    b.next();
+                    a.next()
+                }
+                (Some(_), None) => a.next(),
+                (None, _) => b.next(),
+            };
+            match value {
+                Some(value) => values.push(value),
+                None => break,
+            }
+        }
+        // the union of two non-empty containers is not empty
+        ChunkContainer::from_sorted(&values).unwrap()
+    }
+
+    /// Return the values of this container which are not in the other one, if any.
+    pub fn difference(&self, other: &ChunkContainer) -> Option<ChunkContainer> {
+        if let (ChunkContainer::Bitmap(a), ChunkContainer::Bitmap(b)) = (self, other) {
+            return ChunkContainer::from_words(
+                a.high_bits.iter().zip(b.high_bits.iter()).map(|(a, b)| a & !b).collect()
+            );
+        }
+        let values = self.iter().filter(|value| !other.contains(*value)).collect::<Vec<_>>();
+        match values.is_empty() {
+            true => None,
+            false => ChunkContainer::from_sorted(&values).ok(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Two-level set of u64 in the style of Roaring bitmaps.
+///
+/// The values are split in chunks by their high 48 bits and the low 16 bits
+/// of the values of each chunk are stored in a `ChunkContainer`, choosing for
+/// each chunk the smallest between a sorted array, a `SimpleSelect` bitmap
+/// and a strict `EliasFano`. This adapts to distributions which are sparse
+/// in some regions of the universe and dense in others.
+///
+/// ```
+/// # use elias_fano_rust::ChunkedSet;
+/// let mut values = vec![3, 1 << 40, (1 << 40) + 7];
+/// values.extend((1 << 20..(1 << 20) + 50_000).step_by(2));
+/// values.sort();
+/// let set = ChunkedSet::from_vec(&values).unwrap();
+/// assert!(set.contains(1 << 40));
+/// assert!(!set.contains((1 << 20) + 1));
+/// assert_eq!(set.rank(1 << 40), values.len() as u64 - 2);
+/// assert_eq!(set.select(1), Some(1 << 20));
+///
+/// let other = ChunkedSet::from_vec(&[3, 5, 1 << 40]).unwrap();
+/// assert_eq!(set.intersection(&other).iter().collect::<Vec<_>>(), vec![3, 1 << 40]);
+/// ```
+pub struct ChunkedSet {
+    /// The high bits of the values of each chunk, sorted.
+    keys: Vec<u64>,
+    /// The low bits of the values of each chunk.
+    containers: Vec<ChunkContainer>,
+    /// The number of values before each chunk, with the total at the end.
+    offsets: Vec<u64>,
+}
+
+impl ChunkedSet {
+    /// Create a set from the sorted keys and their non-empty containers.
+    fn from_parts(keys: Vec<u64>, containers: Vec<ChunkContainer>) -> ChunkedSet {
+        let mut offsets = Vec::with_capacity(keys.len() + 1);
+        let mut total = 0;
+        offsets.push(total);
+        for container in &containers {
+            total += container.len();
+            offsets.push(total);
+        }
+        ChunkedSet{keys, containers, offsets}
+    }
+
+    /// Create a set from an iterator of **strictly increasing** values.
+    pub fn from_iter(values: impl Iterator<Item = u64>) -> Result<ChunkedSet, String> {
+        let mut builder = ChunkedSetBuilder::new();
+        let mut last_value = None;
+        for value in values {
+            if let Some(last_value) = last_value {
+                if value <= last_value {
+                    return Err(format!(
+                        concat!(
+                            "The values must be strictly increasing, ",
+                            "but the value {} follows the value {}."
+                        ),
+                        value, last_value
+                    ));
+                }
+            }
+            last_value = Some(value);
+            builder.insert(value);
+        }
+        builder.build()
+    }
+
+    /// Create a set from a slice of **strictly increasing** values.
+    pub fn from_vec(values: &[u64]) -> Result<ChunkedSet, String> {
+        ChunkedSet::from_iter(values.iter().cloned())
+    }
+
+    /// Return the number of values in the set.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        *self.offsets.last().unwrap()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the number of chunks with at least one value.
+    #[inline]
+    pub fn number_of_chunks(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Return an iterator over the high bits of each chunk and its container.
+    #[inline]
+    pub fn chunks(&self) -> impl Iterator<Item = (u64, &ChunkContainer)> + '_ {
+        self.keys.iter().cloned().zip(self.containers.iter())
+    }
+
+    #[inline]
+    pub fn contains(&self, value: u64) -> bool {
+        match self.keys.binary_search(&(value >> CHUNK_SHIFT)) {
+            Ok(index) => self.containers[index].contains((value & CHUNK_MASK) as u16),
+            Err(_) => false,
+        }
+    }
+
+    /// Return the number of values in the set which are smaller than `value`.
+    #[inline]
+    pub fn rank(&self, value: u64) -> u64 {
+        match self.keys.binary_search(&(value >> CHUNK_SHIFT)) {
+            Ok(index) => self.offsets[index]
+                + self.containers[index].rank((value & CHUNK_MASK) as u16),
+            Err(index) => self.offsets[index],
+        }
+    }
+
+    /// Return the value of the chosen index, if present.
+    #[inline]
+    pub fn select(&self, index: u64) -> Option<u64> {
+        if index >= self.len() {
+            return None;
+        }
+        let chunk = self.offsets.partition_point(|offset| *offset <= index) - 1;
+        let low = self.containers[chunk].unchecked_select(index - self.offsets[chunk]);
+        Some(self.keys[chunk] << CHUNK_SHIFT | low as u64)
+    }
+
+    /// Return an iterator over the values in the set.
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        self.chunks().flat_map(|(key, container)| {
+            container.iter().map(move |low| key << CHUNK_SHIFT | low as u64)
+        })
+    }
+
+    /// Return the memory used in bytes
+    pub fn size(&self) -> usize {
+        3 * core::mem::size_of::<Vec<u64>>()
+            + (self.keys.capacity() + self.offsets.capacity()) * core::mem::size_of::<u64>()
+            + self.containers.iter().map(ChunkContainer::size).sum::<usize>()
+    }
+
+    /// Merge the chunks of the two sets, calling `merge` on the containers
+    /// of the chunks which are in both the sets. The chunks which are only
+    /// in one of the sets are kept if the respective flag is set.
+    fn merge(
+        &self,
+        other: &ChunkedSet,
+        keep_self: bool,
+        keep_other: bool,
+        merge: impl Fn(&ChunkContainer, &ChunkContainer) -> Option<ChunkContainer>,
+    ) -> ChunkedSet {
+        let mut keys = Vec::new();
+        let mut containers = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.keys.len() || j < other.keys.len() {
+            let self_key = self.keys.get(i).cloned().unwrap_or(u64::MAX);
+            let other_key = other.keys.get(j).cloned().unwrap_or(u64::MAX);
+            if j == other.keys.len() || (i < self.keys.len() && self_key < other_key) {
+                if keep_self {
+                    keys.push(self_key);
+                    containers.push(self.containers[i].clone());
+                }
+                i += 1;
+            } else if i == self.keys.len() || other_key < self_key {
+                if keep_other {
+                    keys.push(other_key);
+                    containers.push(other.containers[j].clone());
+                }
+                j += 1;
+            } else {
+                if let Some(container) = merge(&self.containers[i], &other.containers[j]) {
+                    keys.push(self_key);
+                    containers.push(container);
+                }
+                i += 1;
+                j += 1;
+            }
+        }
+        ChunkedSet::from_parts(keys, containers)
+    }
+
+    /// Return the set of the values which are in both the sets.
+    pub fn intersection(&self, other: &ChunkedSet) -> ChunkedSet {
+        self.merge(other, false, false, ChunkContainer::intersection)
+    }
+
+    /// Return the set of the values which are in at least one of the sets.
+    pub fn union(&self, other: &ChunkedSet) -> ChunkedSet {
+        self.merge(other, true, true, |a, b| Some(a.union(b)))
+    }
+
+    /// Return the set of the values of this set which are not in the other one.
+    pub fn difference(&self, other: &ChunkedSet) -> ChunkedSet {
+        self.merge(other, true, false, ChunkContainer::difference)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+/// Builder of a `ChunkedSet` which accepts the values in any order.
+///
+/// ```
+/// # use elias_fano_rust::ChunkedSetBuilder;
+/// let mut builder = ChunkedSetBuilder::new();
+/// for value in [1 << 33, 5, 1 << 17, 5] {
+///     builder.insert(value);
+/// }
+/// let set = builder.build().unwrap();
+/// assert_eq!(set.iter().collect::<Vec<_>>(), vec![5, 1 << 17, 1 << 33]);
+/// ```
+pub struct ChunkedSetBuilder {
+    chunks: alloc::collections::BTreeMap<u64, Vec<u16>>,
+}
+
+impl ChunkedSetBuilder {
+    pub fn new() -> ChunkedSetBuilder {
+        ChunkedSetBuilder::default()
+    }
+
+    /// Add a value to the set, the duplicated values are ignored.
+    #[inline]
+    pub fn insert(&mut self, value: u64) {
+        self.chunks.entry(value >> CHUNK_SHIFT)
+            .or_default()
+            .push((value & CHUNK_MASK) as u16);
+    }
+
+    /// Sort the values and build the container of each chunk.
+    pub fn build(self) -> Result<ChunkedSet, String> {
+        let mut keys = Vec::with_capacity(self.chunks.len());
+        let mut containers = Vec::with_capacity(self.chunks.len());
+        for (key, mut values) in self.chunks {
+            values.sort_unstable();
+            values.dedup();
+            keys.push(key);
+            containers.push(ChunkContainer::from_sorted(&values)?);
+        }
+        Ok(ChunkedSet::from_parts(keys, containers))
+    }
+}
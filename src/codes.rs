@@ -0,0 +1,504 @@
+//! Instantaneous codes for integers over a bitstream of u64 words.
+//!
+//! The bits are written starting from the least significant bit of each
+//! word, so a value written with `write_bits` can be read back with a single
+//! unaligned read, as for the low-bits of EliasFano.
+//! The gamma, delta and zeta codes encode `value + 1`
+//! so that 0 can be represented.
+use super::*;
+
+/// Return the number of bits of the given value, so `floor(log2(value)) + 1`.
+#[inline(always)]
+fn bit_length(value: u128) -> u64 {
+    128 - value.leading_zeros() as u64
+}
+
+/// Return the number of bits used by the unary code of `value`.
+#[inline]
+pub fn unary_len(value: u64) -> u64 {
+    value + 1
+}
+
+/// Return the number of bits used by the Elias gamma code of `value`.
+#[inline]
+pub fn gamma_len(value: u64) -> u64 {
+    2 * (bit_length(value as u128 + 1) - 1) + 1
+}
+
+/// Return the number of bits used by the Elias delta code of `value`.
+#[inline]
+pub fn delta_len(value: u64) -> u64 {
+    let bits = bit_length(value as u128 + 1) - 1;
+    gamma_len(bits) + bits
+}
+
+/// Return the number of bits used by the minimal binary code of `value` in [0, `max`).
+#[inline]
+fn minimal_binary_len(value: u128, max: u128) -> u64 {
+    let bits = bit_length(max - 1);
+    if bits == 0 {
+        return 0;
+    }
+    let threshold = (1_u128 << bits) - max;
+    match value < threshold {
+        true => bits - 1,
+        false => bits,
+    }
+}
+
+/// Return the number of bits used by the Golomb code of `value` with modulus `b`,
+/// which must be positive.
+#[inline]
+pub fn golomb_len(value: u64, b: u64) -> u64 {
+    unary_len(value / b) + minimal_binary_len((value % b) as u128, b as u128)
+}
+
+/// Return the number of bits used by the Rice code of `value` with `k` bits.
+#[inline]
+pub fn rice_len(value: u64, k: u64) -> u64 {
+    unary_len(shr(value, k)) + k
+}
+
+/// Return the number of bits used by the zeta code of `value` with shrinking factor `k`,
+/// which must be in [1, 63].
+#[inline]
+pub fn zeta_len(value: u64, k: u64) -> u64 {
+    let value = value as u128 + 1;
+    let h = (bit_length(value) - 1) / k;
+    let left = 1_u128 << (h * k);
+    unary_len(h) + minimal_binary_len(value - left, (1_u128 << ((h + 1) * k)) - left)
+}
+
+/// Return the number of bits used by the VByte code of `value`.
+#[inline]
+pub fn vbyte_len(value: u64) -> u64 {
+    8 * 1_u64.max(bit_length(value as u128).div_ceil(7))
+}
+
+#[inline]
+fn check_golomb_modulus(b: u64) -> Result<(), String> {
+    if b == 0 {
+        return Err("The modulus of the Golomb code must be positive.".to_string());
+    }
+    Ok(())
+}
+
+#[inline]
+fn check_rice_k(k: u64) -> Result<(), String> {
+    if k > WORD_SIZE {
+        return Err(format!(
+            "The number of bits of the Rice code must be at most 64 but it's {}.",
+            k
+        ));
+    }
+    Ok(())
+}
+
+#[inline]
+fn check_zeta_k(k: u64) -> Result<(), String> {
+    if k == 0 || k >= WORD_SIZE {
+        return Err(format!(
+            "The shrinking factor of the zeta code must be in [1, 63] but it's {}.",
+            k
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+/// Append-only bitstream of u64 words.
+///
+/// ```
+/// # use elias_fano_rust::BitWriter;
+/// let mut writer = BitWriter::new();
+/// writer.write_gamma(10);
+/// writer.write_zeta(1_000, 3).unwrap();
+/// writer.write_vbyte(u64::MAX);
+///
+/// let mut reader = writer.reader();
+/// assert_eq!(reader.read_gamma().unwrap(), 10);
+/// assert_eq!(reader.read_zeta(3).unwrap(), 1_000);
+/// assert_eq!(reader.read_vbyte().unwrap(), u64::MAX);
+/// assert!(reader.read_gamma().is_err());
+/// ```
+pub struct BitWriter {
+    data: Vec<u64>,
+    len: u64,
+}
+
+impl BitWriter {
+    pub fn new() -> BitWriter {
+        BitWriter::default()
+    }
+
+    /// Allocate a bitstream with enough memory for `capacity` bits.
+    pub fn with_capacity(capacity: u64) -> BitWriter {
+        BitWriter{
+            data: Vec::with_capacity(((capacity >> WORD_SHIFT) + 2) as usize),
+            len: 0,
+        }
+    }
+
+    /// Return the number of bits written.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the words of the bitstream, the bits after `len()` are zeros.
+    #[inline]
+    pub fn as_slice(&self) -> &[u64] {
+        &self.data
+    }
+
+    /// Consume the writer and return the words and the number of bits written.
+    #[inline]
+    pub fn into_inner(self) -> (Vec<u64>, u64) {
+        (self.data, self.len)
+    }
+
+    /// Return a reader over the bits written so far.
+    #[inline]
+    pub fn reader(&self) -> BitReader<'_> {
+        BitReader::new(&self.data, self.len)
+    }
+
+    /// Write the `n_bits` lower bits of `value`, `n_bits` must be at most 64.
+    #[inline]
+    pub fn write_bits(&mut self, value: u64, n_bits: u64) {
+        debug_assert!(n_bits <= WORD_SIZE);
+        // keep an extra word so that the write is branchless
+        let size = (((self.len + n_bits) >> WORD_SHIFT) + 2) as usize;
+        if self.data.len() < size {
+            self.data.resize(size, 0);
+        }
+        safe_write_bits(&mut self.data, self.len, value, n_bits);
+        self.len += n_bits;
+    }
+
+    /// Write the `n_bits` lower bits of `value`, `n_bits` must be at most 128.
+    #[inline]
+    fn write_wide_bits(&mut self, value: u128, n_bits: u64) {
+        if n_bits <= WORD_SIZE {
+            return self.write_bits(value as u64, n_bits);
+        }
+        self.write_bits(value as u64, WORD_SIZE);
+        self.write_bits((value >> WORD_SIZE) as u64, n_bits - WORD_SIZE);
+    }
+
+    /// Write `value` zeros followed by a one.
+    #[inline]
+    pub fn write_unary(&mut self, value: u64) {
+        // the words are zero-initialized so the zeros are just skipped
+        self.len += value;
+        self.write_bits(1, 1);
+    }
+
+    /// Write `value` in [0, `max`) with the minimal binary code, the
+    /// most significant bits are written first so that the reader can
+    /// decide if it needs to read the last bit.
+    #[inline]
+    pub(crate) fn write_minimal_binary(&mut self, value: u128, max: u128) {
+        let bits = bit_length(max - 1);
+        if bits == 0 {
+            return;
+        }
+        let threshold = (1_u128 << bits) - max;
+        if value < threshold {
+            self.write_wide_bits(value, bits - 1);
+        } else {
+            let code = value + threshold;
+            self.write_wide_bits(code >> 1, bits - 1);
+            self.write_bits((code & 1) as u64, 1);
+        }
+    }
+
+    /// Write the Elias gamma code of `value`.
+    #[inline]
+    pub fn write_gamma(&mut self, value: u64) {
+        let value = value as u128 + 1;
+        let bits = bit_length(value) - 1;
+        self.write_unary(bits);
+        self.write_wide_bits(value, bits);
+    }
+
+    /// Write the Elias delta code of `value`.
+    #[inline]
+    pub fn write_delta(&mut self, value: u64) {
+        let value = value as u128 + 1;
+        let bits = bit_length(value) - 1;
+        self.write_gamma(bits);
+        self.write_wide_bits(value, bits);
+    }
+
+    /// Write the Golomb code of `value` with modulus `b`.
+    #[inline]
+    pub fn write_golomb(&mut self, value: u64, b: u64) -> Result<(), String> {
+        check_golomb_modulus(b)?;
+        self.write_unary(value / b);
+        self.write_minimal_binary((value % b) as u128, b as u128);
+        Ok(())
+    }
+
+    /// Write the Rice code of `value` with `k` bits, so the Golomb code
+    /// with modulus `2^k`. `k` must be at most 64.
+    #[inline]
+    pub fn write_rice(&mut self, value: u64, k: u64) -> Result<(), String> {
+        check_rice_k(k)?;
+        self.write_unary(shr(value, k));
+        self.write_bits(value, k);
+        Ok(())
+    }
+
+    /// Write the zeta code of `value` with shrinking factor `k`,
+    /// which must be in [1, 63]. With `k = 1` it's the gamma code.
+    #[inline]
+    pub fn write_zeta(&mut self, value: u64, k: u64) -> Result<(), String> {
+        check_zeta_k(k)?;
+        let value = value as u128 + 1;
+        let h = (bit_length(value) - 1) / k;
+        let left = 1_u128 << (h * k);
+        self.write_unary(h);
+        self.write_minimal_binary(value - left, (1_u128 << ((h + 1) * k)) - left);
+        Ok(())
+    }
+
+    /// Write the VByte code of `value`, groups of 7 bits from the least
+    /// significant one, each followed by a continuation bit.
+    #[inline]
+    pub fn write_vbyte(&mut self, mut value: u64) {
+        loop {
+            let group = value & 0x7f;
+            value >>= 7;
+            if value == 0 {
+                self.write_bits(group, 8);
+                return;
+            }
+            self.write_bits(group | 0x80, 8);
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Reader over a bitstream written by `BitWriter`.
+pub struct BitReader<'a> {
+    data: &'a [u64],
+    len: u64,
+    position: u64,
+}
+
+impl<'a> BitReader<'a> {
+    /// Create a reader over the first `len` bits of the given words.
+    pub fn new(data: &'a [u64], len: u64) -> BitReader<'a> {
+        BitReader{
+            data,
+            len: len.min(data.len() as u64 * WORD_SIZE),
+            position: 0,
+        }
+    }
+
+    /// Return the index of the next bit that will be read.
+    #[inline]
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Move the reader to the given bit.
+    #[inline]
+    pub fn set_position(&mut self, position: u64) -> Result<(), String> {
+        if position > self.len {
+            return Err(format!(
+                "Cannot move to the bit {} of a bitstream of {} bits.",
+                position, self.len
+            ));
+        }
+        self.position = position;
+        Ok(())
+    }
+
+    /// Return the number of bits which can still be read.
+    #[inline]
+    pub fn remaining(&self) -> u64 {
+        self.len - self.position
+    }
+
+    #[inline]
+    fn check_remaining(&self, n_bits: u64) -> Result<(), String> {
+        if n_bits > self.remaining() {
+            return Err(format!(
+                "Cannot read {} bits at position {} of a bitstream of {} bits.",
+                n_bits, self.position, self.len
+            ));
+        }
+        Ok(())
+    }
+
+    /// Read `n_bits` bits, `n_bits` must be at most 64.
+    #[inline]
+    pub fn read_bits(&mut self, n_bits: u64) -> Result<u64, String> {
+        debug_assert!(n_bits <= WORD_SIZE);
+        self.check_remaining(n_bits)?;
+        if n_bits == 0 {
+            return Ok(0);
+        }
+        let base = (self.position >> WORD_SHIFT) as usize;
+        let offset = self.position & WORD_MASK;
+        // the last word might not be followed by a padding word
+        let mut result = shr(self.data[base], offset);
+        if offset + n_bits > WORD_SIZE {
+            result |= shl(self.data[base + 1], WORD_SIZE - offset);
+        }
+        self.position += n_bits;
+        Ok(result & shr(u64::MAX, WORD_SIZE - n_bits))
+    }
+
+    /// Read `n_bits` bits, `n_bits` must be at most 128.
+    #[inline]
+    fn read_wide_bits(&mut self, n_bits: u64) -> Result<u128, String> {
+        if n_bits <= WORD_SIZE {
+            return Ok(self.read_bits(n_bits)? as u128);
+        }
+        let lower = self.read_bits(WORD_SIZE)? as u128;
+        let higher = self.read_bits(n_bits - WORD_SIZE)? as u128;
+        Ok((higher << WORD_SIZE) | lower)
+    }
+
+    /// Read a value written with the unary code.
+    #[inline]
+    pub fn read_unary(&mut self) -> Result<u64, String> {
+        let start = self.position;
+        let mut position = self.position;
+        while position < self.len {
+            let word = self.data[(position >> WORD_SHIFT) as usize] >> (position & WORD_MASK);
+            if word != 0 {
+                position += word.trailing_zeros() as u64;
+                break;
+            }
+            position += WORD_SIZE - (position & WORD_MASK);
+        }
+        if position >= self.len {
+            return Err(format!(
+                "The unary code starting at position {} is not terminated.",
+                start
+            ));
+        }
+        self.position = position + 1;
+        Ok(position - start)
+    }
+
+    /// Read a value written with the minimal binary code in [0, `max`).
+    #[inline]
+    pub(crate) fn read_minimal_binary(&mut self, max: u128) -> Result<u128, String> {
+        let bits = bit_length(max - 1);
+        if bits == 0 {
+            return Ok(0);
+        }
+        let threshold = (1_u128 << bits) - max;
+        let prefix = self.read_wide_bits(bits - 1)?;
+        if prefix < threshold {
+            return Ok(prefix);
+        }
+        let last = self.read_bits(1)? as u128;
+        Ok(((prefix << 1) | last) - threshold)
+    }
+
+    /// Convert a decoded `value + 1` back to the value, checking that it fits.
+    #[inline]
+    fn to_value(value: u128) -> Result<u64, String> {
+        if value == 0 || value - 1 > u64::MAX as u128 {
+            return Err(format!(
+                "The decoded value {} is not valid, the bitstream is corrupted.",
+                value.wrapping_sub(1)
+            ));
+        }
+        Ok((value - 1) as u64)
+    }
+
+    /// Read a value written with the Elias gamma code.
+    #[inline]
+    pub fn read_gamma(&mut self) -> Result<u64, String> {
+        let bits = self.read_unary()?;
+        if bits > WORD_SIZE {
+            return Err(format!(
+                "The gamma code has {} bits, the bitstream is corrupted.",
+                bits
+            ));
+        }
+        let lower = self.read_wide_bits(bits)?;
+        BitReader::to_value((1_u128 << bits) | lower)
+    }
+
+    /// Read a value written with the Elias delta code.
+    #[inline]
+    pub fn read_delta(&mut self) -> Result<u64, String> {
+        let bits = self.read_gamma()?;
+        if bits > WORD_SIZE {
+            return Err(format!(
+                "The delta code has {} bits, the bitstream is corrupted.",
+                bits
+            ));
+        }
+        let lower = self.read_wide_bits(bits)?;
+        BitReader::to_value((1_u128 << bits) | lower)
+    }
+
+    /// Read a value written with the Golomb code with modulus `b`.
+    #[inline]
+    pub fn read_golomb(&mut self, b: u64) -> Result<u64, String> {
+        check_golomb_modulus(b)?;
+        let quotient = self.read_unary()? as u128;
+        let reminder = self.read_minimal_binary(b as u128)?;
+        BitReader::to_value(quotient * b as u128 + reminder + 1)
+    }
+
+    /// Read a value written with the Rice code with `k` bits.
+    #[inline]
+    pub fn read_rice(&mut self, k: u64) -> Result<u64, String> {
+        check_rice_k(k)?;
+        let quotient = self.read_unary()? as u128;
+        let reminder = self.read_bits(k)? as u128;
+        BitReader::to_value((quotient << k) + reminder + 1)
+    }
+
+    /// Read a value written with the zeta code with shrinking factor `k`.
+    #[inline]
+    pub fn read_zeta(&mut self, k: u64) -> Result<u64, String> {
+        check_zeta_k(k)?;
+        let h = self.read_unary()?;
+        if h * k > WORD_SIZE {
+            return Err(format!(
+                "The zeta code has {} blocks of {} bits, the bitstream is corrupted.",
+                h, k
+            ));
+        }
+        let left = 1_u128 << (h * k);
+        let value = self.read_minimal_binary((1_u128 << ((h + 1) * k)) - left)?;
+        BitReader::to_value(value + left)
+    }
+
+    /// Read a value written with the VByte code.
+    #[inline]
+    pub fn read_vbyte(&mut self) -> Result<u64, String> {
+        let mut result: u128 = 0;
+        let mut shift = 0;
+        loop {
+            let group = self.read_bits(8)?;
+            result |= ((group & 0x7f) as u128) << shift;
+            if group & 0x80 == 0 {
+                break;
+            }
+            shift += 7;
+            if shift >= WORD_SIZE {
+                return Err(
+                    "The VByte code is longer than 64 bits, the bitstream is corrupted.".to_string()
+                );
+            }
+        }
+        BitReader::to_value(result + 1)
+    }
+}
@@ -0,0 +1,311 @@
+use super::*;
+#[cfg(feature = "std")]
+use rayon::iter::IndexedParallelIterator;
+#[cfg(feature = "std")]
+use rayon::prelude::*;
+#[cfg(feature = "std")]
+use std::io::{Read, Write};
+use core::sync::atomic::AtomicU64;
+
+/// Check that the width of the values is supported.
+#[inline]
+fn check_width(width: u64) -> Result<(), String> {
+    if width > WORD_SIZE {
+        return Err(format!(
+            "The width {} is not supported, the values can be at most {} bits.",
+            width, WORD_SIZE
+        ));
+    }
+    Ok(())
+}
+
+/// Check that the value can be stored in `width` bits.
+#[inline]
+fn check_value(value: u64, width: u64) -> Result<(), String> {
+    if shr(value, width) != 0 {
+        return Err(format!(
+            "The value {} does not fit in {} bits.",
+            value, width
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug)]
+/// Vector of integers of fixed width, packed one after the other
+/// in a vector of words, this is the same encoding of the low-bits of EliasFano.
+///
+/// ```
+/// # use elias_fano_rust::CompactVec;
+/// let mut vector = CompactVec::new(5, 3).unwrap();
+/// vector.set(1, 31).unwrap();
+/// vector.set(1, 4).unwrap();
+/// vector.push(17).unwrap();
+/// assert!(vector.push(32).is_err());
+/// assert_eq!(vector.iter().collect::<Vec<_>>(), vec![0, 4, 0, 17]);
+/// ```
+pub struct CompactVec {
+    data: Vec<u64>,
+    width: u64,
+    len: usize,
+}
+
+impl CompactVec {
+    /// Create a vector of `len` zeros of `width` bits.
+    pub fn new(width: u64, len: usize) -> Result<CompactVec, String> {
+        check_width(width)?;
+        Ok(CompactVec{
+            data: vec![0; get_vec_size(width, len) as usize],
+            width,
+            len,
+        })
+    }
+
+    /// Create an empty vector of values of `width` bits, with enough
+    /// memory for `capacity` values.
+    pub fn with_capacity(width: u64, capacity: usize) -> Result<CompactVec, String> {
+        let mut result = CompactVec::new(width, capacity)?;
+        result.len = 0;
+        Ok(result)
+    }
+
+    /// Create a vector from a slice of values, using the minimum width
+    /// needed to store the biggest one.
+    pub fn from_vec(values: &[u64]) -> Result<CompactVec, String> {
+        let max = values.iter().cloned().max().unwrap_or(0);
+        let mut result = CompactVec::with_capacity(WORD_SIZE - max.leading_zeros() as u64, values.len())?;
+        for value in values {
+            result.push(*value)?;
+        }
+        Ok(result)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the number of bits of each value.
+    #[inline]
+    pub fn width(&self) -> u64 {
+        self.width
+    }
+
+    /// Return the value at the given index, if present.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<u64> {
+        match index < self.len {
+            true => Some(self.unchecked_get(index)),
+            false => None,
+        }
+    }
+
+    /// Return the value at the given index without checking it against the length.
+    /// The reads are always bounds-checked, also with the `unsafe` feature,
+    /// so an index past the allocated values panics instead of reading out of bounds.
+    #[inline]
+    pub fn unchecked_get(&self, index: usize) -> u64 {
+        safe_read_bits(&self.data, index as u64 * self.width, self.width)
+    }
+
+    /// Overwrite the value at the given index.
+    #[inline]
+    pub fn set(&mut self, index: usize, value: u64) -> Result<(), String> {
+        if index >= self.len {
+            return Err(format!(
+                "Given index {} is out of bound on a vector with {} elements.",
+                index, self.len
+            ));
+        }
+        check_value(value, self.width)?;
+        self.unchecked_set(index, value);
+        Ok(())
+    }
+
+    /// Overwrite the value at the given index without checking it against the
+    /// length nor the value against the width. Like `unchecked_get`, an index
+    /// past the allocated values panics instead of writing out of bounds.
+    #[inline]
+    pub fn unchecked_set(&mut self, index: usize, value: u64) {
+        let pos = index as u64 * self.width;
+        safe_clear_bits(&mut self.data, pos, self.width);
+        safe_write_bits(&mut self.data, pos, value, self.width);
+    }
+
+    /// Add a value at the end of the vector.
+    #[inline]
+    pub fn push(&mut self, value: u64) -> Result<(), String> {
+        check_value(value, self.width)?;
+        let size = get_vec_size(self.width, self.len + 1) as usize;
+        if self.data.len() < size {
+            self.data.resize(size, 0);
+        }
+        self.len += 1;
+        self.unchecked_set(self.len - 1, value);
+        Ok(())
+    }
+
+    /// Return an iterator over the values.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        (0..self.len).map(move |index| self.unchecked_get(index))
+    }
+
+    /// Return a parallel iterator over the values.
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn par_iter(&self) -> impl IndexedParallelIterator<Item = u64> + '_ {
+        (0..self.len).into_par_iter().map(move |index| self.unchecked_get(index))
+    }
+
+    /// Return the memory used in bytes
+    #[inline]
+    pub fn size(&self) -> usize {
+        core::mem::size_of::<u64>() + core::mem::size_of::<usize>()
+            + self.data.capacity() * core::mem::size_of::<u64>()
+            + core::mem::size_of::<Vec<u64>>()
+    }
+
+    /// Reduces the memory allocated to the minimum needed.
+    pub fn shrink_to_fit(&mut self) {
+        self.data.truncate(get_vec_size(self.width, self.len) as usize);
+        self.data.shrink_to_fit();
+    }
+
+    /// Write the vector to the given writer.
+    ///
+    /// The format is a sequence of little-endian u64:
+    /// `width`, `len` and then the words prefixed by their length.
+    #[cfg(feature = "std")]
+    pub fn serialize(&self, writer: &mut impl Write) -> Result<(), String> {
+        write_u64(writer, self.width)?;
+        write_u64(writer, self.len as u64)?;
+        write_vec(writer, &self.data)
+    }
+
+    /// Read a vector written by `serialize`.
+    #[cfg(feature = "std")]
+    pub fn deserialize(reader: &mut impl Read) -> Result<CompactVec, String> {
+        let width = read_u64(reader)?;
+        check_width(width)?;
+        let len = read_u64(reader)? as usize;
+        let data = read_vec(reader)?;
+        if (data.len() as u64) < get_vec_size(width, len) {
+            return Err(format!(
+                concat!(
+                    "The serialized CompactVec is corrupted, it has {} words ",
+                    "but {} values of {} bits need {} words."
+                ),
+                data.len(), len, width, get_vec_size(width, len)
+            ));
+        }
+        Ok(CompactVec{data, width, len})
+    }
+}
+
+impl PartialEq for CompactVec {
+    fn eq(&self, other: &CompactVec) -> bool {
+        // the padding and the capacity are not compared
+        self.width == other.width
+            && self.len == other.len
+            && self.iter().eq(other.iter())
+    }
+}
+
+#[derive(Debug)]
+/// Vector of integers of fixed width which can be written concurrently,
+/// as long as two threads do not write the same index at the same time.
+/// Once it's filled, it can be converted to a `CompactVec`.
+///
+/// ```
+/// # use elias_fano_rust::AtomicCompactVec;
+/// use rayon::prelude::*;
+/// let vector = AtomicCompactVec::new(7, 100).unwrap();
+/// (0..100).into_par_iter().for_each(|i| vector.set(i, i as u64).unwrap());
+/// assert_eq!(vector.get(42), Some(42));
+/// let vector = vector.into_compact_vec();
+/// assert_eq!(vector.iter().collect::<Vec<_>>(), (0..100).collect::<Vec<_>>());
+/// ```
+pub struct AtomicCompactVec {
+    data: Vec<AtomicU64>,
+    width: u64,
+    len: usize,
+}
+
+impl AtomicCompactVec {
+    /// Create a vector of `len` zeros of `width` bits.
+    pub fn new(width: u64, len: usize) -> Result<AtomicCompactVec, String> {
+        check_width(width)?;
+        Ok(AtomicCompactVec{
+            data: (0..get_vec_size(width, len)).map(|_| AtomicU64::new(0)).collect(),
+            width,
+            len,
+        })
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the number of bits of each value.
+    #[inline]
+    pub fn width(&self) -> u64 {
+        self.width
+    }
+
+    /// Return the value at the given index, if present.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<u64> {
+        match index < self.len {
+            true => Some(concurrent_read_bits(&self.data, index as u64 * self.width, self.width)),
+            false => None,
+        }
+    }
+
+    /// Overwrite the value at the given index, this can be called
+    /// by multiple threads on different indices.
+    #[inline]
+    pub fn set(&self, index: usize, value: u64) -> Result<(), String> {
+        if index >= self.len {
+            return Err(format!(
+                "Given index {} is out of bound on a vector with {} elements.",
+                index, self.len
+            ));
+        }
+        check_value(value, self.width)?;
+        let pos = index as u64 * self.width;
+        concurrent_clear_bits(&self.data, pos, self.width);
+        concurrent_write_bits(&self.data, pos, value, self.width);
+        Ok(())
+    }
+
+    /// Consume the vector and return the equivalent `CompactVec`.
+    pub fn into_compact_vec(self) -> CompactVec {
+        CompactVec{
+            data: self.data.into_iter().map(AtomicU64::into_inner).collect(),
+            width: self.width,
+            len: self.len,
+        }
+    }
+}
+
+impl From<CompactVec> for AtomicCompactVec {
+    fn from(vector: CompactVec) -> AtomicCompactVec {
+        AtomicCompactVec{
+            data: vector.data.into_iter().map(AtomicU64::new).collect(),
+            width: vector.width,
+            len: vector.len,
+        }
+    }
+}
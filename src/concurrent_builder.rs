@@ -8,58 +8,43 @@ use rayon::prelude::*;
 /// Once the build is finished this struct can be converted to an EliasFano struct
 /// by calling the `build` method which compute and store the indexes needed for
 /// the constant time select (high-bits).
-pub struct ConcurrentEliasFanoBuilder {
+pub struct ConcurrentEliasFanoBuilder<T: UnsignedInteger = u64> {
     high_bits: Vec<AtomicU64>,
     low_bits: Vec<AtomicU64>,
     number_of_elements: u64,
-    universe: u64,
+    universe: T,
     low_bit_count: u64,
-    low_bit_mask: u64,
+    low_bit_mask: T,
 }
 
-impl Default for ConcurrentEliasFanoBuilder {
+impl<T: UnsignedInteger> Default for ConcurrentEliasFanoBuilder<T> {
     fn default() -> Self {
         ConcurrentEliasFanoBuilder{
             high_bits: Vec::new(),
             low_bits: Vec::new(),
             number_of_elements: 0,
-            universe: u64::MAX,
+            universe: T::MAX,
             low_bit_count: 0,
-            low_bit_mask: 0,
+            low_bit_mask: T::ZERO,
         }
     }
 }
 
-impl ConcurrentEliasFanoBuilder {
-    pub fn new(number_of_elements: u64, universe: u64) -> Result<ConcurrentEliasFanoBuilder, String> {
+impl<T: UnsignedInteger> ConcurrentEliasFanoBuilder<T> {
+    pub fn new(number_of_elements: u64, universe: T) -> Result<ConcurrentEliasFanoBuilder<T>, String> {
         // If the user says that there will be no elements, the builder will 
         // only use the high-bits to store the eventual values
         if number_of_elements == 0 {
             return Ok(ConcurrentEliasFanoBuilder::default());
         }
 
-        // Compute the size of the low bits.
-        let low_bit_count = if universe >= number_of_elements as u64 {
-            (universe as f64 / number_of_elements as f64).log2().floor() as u64
-        } else {
-            0
-        };
-
-        // saturate at the max we can handle
-        if low_bit_count > 64 {
-            return Err(format!(concat!(
-                    "The lowbits are too big, in EliasFano we only support 64 bits for the low parts.",
-                    "The value were universe {} number_of_elements {}"
-                ),
-                universe, number_of_elements
-            ));
-        }
+        let low_bit_count = compute_low_bit_count(universe, number_of_elements as usize)?;
 
         // add 2 to do the ceil and have brenchless primitives.
         let low_size = get_vec_size(low_bit_count, number_of_elements as usize);
         // the number of bits will be at max the number of elements + max(high)
         // we need a ceil, but >> is floor so we add 1
-        let high_size = ((number_of_elements + (universe >> low_bit_count)) as f64 / WORD_SIZE as f64).ceil() as u64;
+        let high_size = ((number_of_elements + universe.shift_right(low_bit_count).to_u64()) as f64 / WORD_SIZE as f64).ceil() as u64;
 
         let high_bits: Vec<_> = (0..high_size).map(|_| AtomicU64::new(0)).collect();
         let low_bits : Vec<_> = (0..low_size ).map(|_| AtomicU64::new(0)).collect();
@@ -68,7 +53,7 @@ impl ConcurrentEliasFanoBuilder {
             universe,
             low_bit_count,
             // Pre-rendered mask to execute a fast version of the mod operation.
-            low_bit_mask: shr(0xffffffffffffffff, 64 - low_bit_count),
+            low_bit_mask: T::mask(low_bit_count),
             high_bits,
             number_of_elements: number_of_elements as u64,
             low_bits,
@@ -79,12 +64,12 @@ impl ConcurrentEliasFanoBuilder {
     /// Write the given value in the elias-fano, this method is
     /// safe from concurrency and allows to build elias-fano in parallel
     /// if the indices of the values are known in advance.
-    pub fn set(&self, index: u64, value: u64) {
-        let high = value >> self.low_bit_count;
+    pub fn set(&self, index: u64, value: T) {
+        let high = value.shift_right(self.low_bit_count).to_u64();
         let low  = value & self.low_bit_mask;
 
         // write the low-bits
-        concurrent_write(&self.low_bits, index, low, self.low_bit_count);
+        T::concurrent_write_packed(&self.low_bits, index, low, self.low_bit_count);
 
         // write the high-bits
         let idx = high + index;
@@ -94,7 +79,7 @@ impl ConcurrentEliasFanoBuilder {
     ///  Consume the builder and returns the built EliasFano struct.
     /// This step is not really parallel and will have to build the
     /// high-bits indices needed for the constant time select.
-    pub fn build(self) -> Result<EliasFano, String> {
+    pub fn build(self) -> Result<EliasFano<T>, String> {
         // Remove the atomic type from the vector
         // this is not supposed to generate any instruction but it's meant to 
         // make the compiler happy.
@@ -122,6 +107,8 @@ impl ConcurrentEliasFanoBuilder {
             low_bits,
             high_bits: SimpleSelect::from_vec(high_bits),
             universe: self.universe,
+            min_value: T::ZERO,
+            strict: false,
             number_of_elements: self.number_of_elements,
             low_bit_count: self.low_bit_count,
             low_bit_mask: self.low_bit_mask,
@@ -135,14 +122,14 @@ impl ConcurrentEliasFanoBuilder {
             // We will initialize them to garbage, and then use the rank and select
             // methods to compute the right values
             last_high_value:0,
-            last_value:0,
+            last_value:T::ZERO,
             last_index: self.number_of_elements,
         };
 
         if self.number_of_elements > 0 {
             let max_value = result.select(self.number_of_elements.saturating_sub(1)).unwrap();
             result.last_value = max_value;
-            result.last_high_value = max_value >> self.low_bit_count;
+            result.last_high_value = result.extract_high_bits(max_value);
         }
 
         Ok(result)
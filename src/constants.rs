@@ -1,4 +1,4 @@
-use std::mem::size_of;
+use core::mem::size_of;
 
 
 /// Vigna uses 8, but in our experiments 10 or 11 provide better trade-offs.
@@ -1,17 +1,34 @@
 use super::*;
+use core::ops::Range;
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct EliasFano {
-    pub low_bits: Vec<u64>,
-    pub high_bits: SimpleSelect,
-    pub universe: u64,
-    pub number_of_elements: u64,
-    pub low_bit_count: u64,
-    pub low_bit_mask: u64,
-    pub last_high_value: u64,
-    pub last_value: u64,
-    pub last_index: u64,
-    pub current_number_of_elements: u64,
+/// Elias-Fano encoding of a sorted sequence of values of type `T`,
+/// by default u64.
+///
+/// ```
+/// # use elias_fano_rust::EliasFano;
+/// let ef = EliasFano::<u32>::from_vec(&[5, 8, 8, 15, 32]).unwrap();
+/// let value: u32 = ef.select(3).unwrap();
+/// assert_eq!(value, 15);
+/// ```
+pub struct EliasFano<T: UnsignedInteger = u64> {
+    pub(crate) low_bits: Vec<u64>,
+    pub(crate) high_bits: SimpleSelect,
+    pub(crate) universe: T,
+    /// The smallest value that can be stored, the values are encoded
+    /// as `value - min_value` so that the high-bits are not wasted
+    /// on the empty prefix of the universe.
+    pub(crate) min_value: T,
+    /// If the values are strictly increasing they are encoded as
+    /// `value - index`, which reduces the universe by the number of elements.
+    pub(crate) strict: bool,
+    pub(crate) number_of_elements: u64,
+    pub(crate) low_bit_count: u64,
+    pub(crate) low_bit_mask: T,
+    pub(crate) last_high_value: u64,
+    pub(crate) last_value: T,
+    pub(crate) last_index: u64,
+    pub(crate) current_number_of_elements: u64,
 }
 
 #[derive(Clone, Debug)]
@@ -29,12 +46,12 @@ impl EliasFanoMemoryStats {
     }
 }
 
-impl EliasFano {
+impl<T: UnsignedInteger> EliasFano<T> {
     /// Return the memory used by each sub-element in bytes
     pub fn memory_stats(&self) -> EliasFanoMemoryStats {
-        use std::mem::size_of;
+        use core::mem::size_of;
         EliasFanoMemoryStats {
-            metadata: 8 * size_of::<u64>(),
+            metadata: 5 * size_of::<u64>() + 4 * size_of::<T>(),
             low_bits: (self.low_bits.capacity() * size_of::<u64>()) + size_of::<Vec<u64>>(),
             high_bits: self.high_bits.size(),
         }
@@ -45,6 +62,27 @@ impl EliasFano {
         self.memory_stats().total()
     }
 
+    /// Return an upper bound of the memory in bytes that an EliasFano with
+    /// `number_of_elements` values in [0, `universe`] uses once shrinked,
+    /// without building it.
+    pub fn estimate_size(universe: T, number_of_elements: usize) -> usize {
+        use core::mem::size_of;
+        let low_bit_count = compute_low_bit_count(universe, number_of_elements).unwrap_or(T::BITS);
+        let (low_words, number_of_zeros) = match number_of_elements {
+            0 => (0, 0),
+            _ => (
+                get_vec_size(low_bit_count, number_of_elements),
+                universe.shift_right(low_bit_count).to_u64(),
+            ),
+        };
+        5 * size_of::<u64>() + 4 * size_of::<T>()
+            + low_words as usize * size_of::<u64>() + size_of::<Vec<u64>>()
+            + SimpleSelect::estimate_size(
+                number_of_elements as u64 + number_of_zeros,
+                number_of_elements as u64,
+            )
+    }
+
     /// Return how much memory is spent for the indices
     /// needed for constant time rank and select in ratio with
     /// the high and low bits vectors.
@@ -93,17 +131,17 @@ impl EliasFano {
     }
 
     #[inline]
-    pub(crate) fn extract_high_bits(&self, value: u64) -> u64 {
-        value >> self.low_bit_count
+    pub(crate) fn extract_high_bits(&self, value: T) -> u64 {
+        value.shift_right(self.low_bit_count).to_u64()
     }
 
     #[inline]
-    pub(crate) fn extract_low_bits(&self, value: u64) -> u64 {
+    pub(crate) fn extract_low_bits(&self, value: T) -> T {
         value & self.low_bit_mask
     }
 
     #[inline]
-    pub(crate) fn extract_high_low_bits(&self, value: u64) -> (u64, u64) {
+    pub(crate) fn extract_high_low_bits(&self, value: T) -> (u64, T) {
         // The following is an efficient mod operation
         // It is the equivalent of executing:
         //
@@ -114,20 +152,86 @@ impl EliasFano {
         (self.extract_high_bits(value), self.extract_low_bits(value))
     }
 
+    /// Merge the high and low parts of the value of position `index`
+    /// and add back the offset and, in strict mode, the index.
+    #[inline]
+    pub(crate) fn build_value(&self, index: u64, high: u64, low: T) -> T {
+        let value = (T::from_u64(high).shift_left(self.low_bit_count) | low) + self.min_value;
+        if self.strict {
+            value + T::from_u64(index)
+        } else {
+            value
+        }
+    }
+
+    /// Return the number of elements whose **encoded** value is smaller than
+    /// the given encoded value.
+    #[inline]
+    pub(crate) fn encoded_rank(&self, value: T) -> u64 {
+        let mut last_encoded_value = self.last_value - self.min_value;
+        if self.strict {
+            last_encoded_value = last_encoded_value - T::from_u64(self.current_number_of_elements - 1);
+        }
+        if value > last_encoded_value {
+            return self.current_number_of_elements;
+        }
+        // split into high and low
+        let (high, low) = self.extract_high_low_bits(value);
+        let mut index = match high == 0 {
+            true => 0,
+            false => self.high_bits.unchecked_select0(high - 1) + 1,
+        };
+
+        // get the first guess
+        let mut ones = index - high;
+        // handle the case where
+        while self.high_bits.unchecked_get(index) && self.read_lowbits(ones) < low {
+            ones += 1;
+            index += 1;
+        }
+
+        ones
+    }
+
+    /// Return the number of elements smaller than `value` in strict mode.
+    ///
+    /// The encoded values are `x_i - i` so the elements smaller than `value`
+    /// are at least the ones whose encoded value is smaller than
+    /// `value - (n - 1)` and at most the ones whose encoded value is smaller
+    /// than `value`. The exact rank is found with a binary search between
+    /// these two bounds, so it takes O(log n) selects.
+    #[inline]
+    pub(crate) fn strict_rank(&self, value: T) -> u64 {
+        let relative_value = value - self.min_value;
+        let max_index = self.current_number_of_elements - 1;
+        let mut low = if relative_value.to_u128() >= max_index as u128 {
+            self.encoded_rank(relative_value - T::from_u64(max_index))
+        } else {
+            0
+        };
+        let mut high = self.encoded_rank(relative_value);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            if self.unchecked_select(middle) < value {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+        low
+    }
+
     #[inline]
     pub(crate) fn build_low_high_bits(
         &mut self,
-        values: impl Iterator<Item = u64>,
+        values: impl Iterator<Item = T>,
     ) -> Result<(), String> {
         values.map(move |value| self.push(value)).collect()
     }
 
     #[inline]
-    pub(crate) fn read_lowbits(&self, index: u64) -> u64 {
-        #[cfg(not(feature = "unsafe"))]
-        return safe_read(&self.low_bits, index, self.low_bit_count);
-        #[cfg(feature = "unsafe")]
-        return unsafe_read(&self.low_bits, index, self.low_bit_count);
+    pub(crate) fn read_lowbits(&self, index: u64) -> T {
+        T::read_packed(&self.low_bits, index, self.low_bit_count)
     }
 
     /// Return the number of elements <= to the given value.
@@ -137,9 +241,12 @@ impl EliasFano {
     /// This means that if in the vector there are multiple equal values,
     /// the index returned will always be the one of the first.
     ///
+    /// In strict mode this is a binary search which takes O(log n),
+    /// see `new_strict`.
+    ///
     /// # Arguments
     ///
-    /// * `value`: u64 - Value whose rank is to be extracted.
+    /// * `value`: T - Value whose rank is to be extracted.
     ///
     /// # Usage example
     ///
@@ -147,7 +254,7 @@ impl EliasFano {
     ///
     /// ```rust
     /// # use elias_fano_rust::EliasFano;
-    /// let vector = [5, 8, 8, 15, 32];
+    /// let vector = [5_u64, 8, 8, 15, 32];
     /// let ef = EliasFano::from_vec(&vector).unwrap();
     ///
     /// assert_eq!(ef.rank(15).unwrap(), 3);
@@ -155,28 +262,35 @@ impl EliasFano {
     /// ```
     ///
     #[inline]
-    pub fn rank(&self, value: u64) -> Option<u64> {
+    pub fn rank(&self, value: T) -> Option<u64> {
         if self.is_empty() {
             return None;
         }
-        if value > self.last_value {
+        if value > self.last_value || value < self.min_value {
             return None;
         }
+        if self.strict {
+            let index = self.strict_rank(value);
+            return match self.unchecked_select(index) == value {
+                true => Some(index),
+                false => None,
+            };
+        }
         // split into high and low
-        let (high, low) = self.extract_high_low_bits(value);
+        let (high, low) = self.extract_high_low_bits(value - self.min_value);
         let mut index = match high == 0 {
             true => 0,
-            false => self.high_bits.select0(high - 1) + 1,
+            false => self.high_bits.unchecked_select0(high - 1) + 1,
         };
         // get the first guess
         let mut ones = index - high;
         // handle the case where
-        while self.high_bits.get(index) && self.read_lowbits(ones) < low {
+        while self.high_bits.unchecked_get(index) && self.read_lowbits(ones) < low {
             ones += 1;
             index += 1;
         }
 
-        if self.high_bits.get(index) && self.read_lowbits(ones) == low {
+        if self.high_bits.unchecked_get(index) && self.read_lowbits(ones) == low {
             Some(ones)
         } else {
             None
@@ -190,9 +304,12 @@ impl EliasFano {
     /// This means that if in the vector there are multiple equal values,
     /// the index returned will always be the one of the first.
     ///
+    /// In strict mode this is a binary search which takes O(log n),
+    /// see `new_strict`.
+    ///
     /// # Arguments
     ///
-    /// * `value`: u64 - Value whose rank is to be extracted.
+    /// * `value`: T - Value whose rank is to be extracted.
     ///
     /// # Usage example
     ///
@@ -200,7 +317,7 @@ impl EliasFano {
     ///
     /// ```rust
     /// # use elias_fano_rust::EliasFano;
-    /// let vector = [5, 8, 8, 15, 32];
+    /// let vector = [5_u64, 8, 8, 15, 32];
     /// let ef = EliasFano::from_vec(&vector).unwrap();
     ///
     /// assert_eq!(ef.unchecked_rank(15), 3);
@@ -209,24 +326,30 @@ impl EliasFano {
     /// ```
     ///
     #[inline]
-    pub fn unchecked_rank(&self, value: u64) -> u64 {
+    pub fn unchecked_rank(&self, value: T) -> u64 {
         if self.is_empty() {
             return 0;
         }
         if value > self.last_value {
             return self.current_number_of_elements;
         }
+        if value <= self.min_value {
+            return 0;
+        }
+        if self.strict {
+            return self.strict_rank(value);
+        }
         // split into high and low
-        let (high, low) = self.extract_high_low_bits(value);
+        let (high, low) = self.extract_high_low_bits(value - self.min_value);
         let mut index = match high == 0 {
             true => 0,
-            false => self.high_bits.select0(high - 1) + 1,
+            false => self.high_bits.unchecked_select0(high - 1) + 1,
         };
 
         // get the first guess
         let mut ones = index - high;
         // handle the case where
-        while self.high_bits.get(index) && self.read_lowbits(ones) < low {
+        while self.high_bits.unchecked_get(index) && self.read_lowbits(ones) < low {
             ones += 1;
             index += 1;
         }
@@ -245,7 +368,7 @@ impl EliasFano {
     ///
     /// * index: u64 - Index of the value to be extract.
     #[inline]
-    pub fn select(&self, index: u64) -> Result<u64, String> {
+    pub fn select(&self, index: u64) -> Result<T, String> {
         match index < self.number_of_elements {
             true => Ok(self.unchecked_select(index)),
             false =>Err(format!(
@@ -261,31 +384,104 @@ impl EliasFano {
     ///
     /// * index: u64 - Index of the value to be extract.
     #[inline]
-    pub fn unchecked_select(&self, index: u64) -> u64 {
-        let high_bits = self.high_bits.select1(index) - index;
+    pub fn unchecked_select(&self, index: u64) -> T {
+        let high_bits = self.high_bits.unchecked_select1(index) - index;
         let low_bits = self.read_lowbits(index);
-        (high_bits << self.low_bit_count) | low_bits
+        self.build_value(index, high_bits, low_bits)
     }
 
+    /// Return the smallest value in the set which is greater or equal
+    /// to the given one, if any.
+    ///
+    /// ```rust
+    /// # use elias_fano_rust::EliasFano;
+    /// let ef = EliasFano::from_vec(&[5_u64, 8, 8, 15, 32]).unwrap();
+    /// assert_eq!(ef.next_geq(9), Some(15));
+    /// assert_eq!(ef.next_geq(8), Some(8));
+    /// assert_eq!(ef.next_geq(33), None);
+    /// ```
     #[inline]
-    pub fn contains(&self, value: u64) -> bool {
-        if value > self.last_value {
+    pub fn next_geq(&self, value: T) -> Option<T> {
+        let index = self.unchecked_rank(value);
+        match index < self.current_number_of_elements {
+            true => Some(self.unchecked_select(index)),
+            false => None,
+        }
+    }
+
+    /// Return if the value is in the set. In strict mode this uses the rank,
+    /// so it takes O(log n), see `new_strict`.
+    #[inline]
+    pub fn contains(&self, value: T) -> bool {
+        if self.is_empty() || value > self.last_value || value < self.min_value {
             return false;
         }
+        if self.strict {
+            return self.unchecked_select(self.strict_rank(value)) == value;
+        }
         // split into high and low
-        let (high, low) = self.extract_high_low_bits(value);
+        let (high, low) = self.extract_high_low_bits(value - self.min_value);
         let mut index = match high == 0 {
             true => 0,
-            false => self.high_bits.select0(high - 1) + 1,
+            false => self.high_bits.unchecked_select0(high - 1) + 1,
         };
         // get the first guess
         let mut ones = index - high;
         // handle the case where
-        while self.high_bits.get(index) && self.read_lowbits(ones) < low {
+        while self.high_bits.unchecked_get(index) && self.read_lowbits(ones) < low {
             ones += 1;
             index += 1;
         }
 
-        self.high_bits.get(index) && self.read_lowbits(ones) == low
+        self.high_bits.unchecked_get(index) && self.read_lowbits(ones) == low
+    }
+
+    /// Return the number of elements in the given range of values.
+    ///
+    /// This is `unchecked_rank(end) - unchecked_rank(start)`, but when both
+    /// ends fall in the same bucket of the high-bits the bucket is found
+    /// once and the two ranks are computed with a single scan.
+    ///
+    /// ```rust
+    /// # use elias_fano_rust::EliasFano;
+    /// let ef = EliasFano::from_vec(&[5_u64, 8, 8, 15, 32]).unwrap();
+    /// assert_eq!(ef.count_in_range(8..16), 3);
+    /// assert_eq!(ef.count_in_range(0..100), 5);
+    /// assert_eq!(ef.count_in_range(9..15), 0);
+    /// ```
+    #[inline]
+    pub fn count_in_range(&self, range: Range<T>) -> u64 {
+        let Range{start, end} = range;
+        if self.is_empty() || start >= end || end <= self.min_value || start > self.last_value {
+            return 0;
+        }
+        let start = start.max(self.min_value);
+        if self.strict || end > self.last_value {
+            return self.unchecked_rank(end) - self.unchecked_rank(start);
+        }
+
+        let (high, start_low) = self.extract_high_low_bits(start - self.min_value);
+        let (end_high, end_low) = self.extract_high_low_bits(end - self.min_value);
+        if high != end_high {
+            return self.unchecked_rank(end) - self.unchecked_rank(start);
+        }
+
+        let mut index = match high == 0 {
+            true => 0,
+            false => self.high_bits.unchecked_select0(high - 1) + 1,
+        };
+        let mut ones = index - high;
+        // skip the elements smaller than the start
+        while self.high_bits.unchecked_get(index) && self.read_lowbits(ones) < start_low {
+            ones += 1;
+            index += 1;
+        }
+        let start_rank = ones;
+        // and then the ones smaller than the end
+        while self.high_bits.unchecked_get(index) && self.read_lowbits(ones) < end_low {
+            ones += 1;
+            index += 1;
+        }
+        ones - start_rank
     }
 }
@@ -0,0 +1,480 @@
+use super::*;
+use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Counter used to give unique names to the temporary files of the builders
+/// living in the same process.
+static BUILDER_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// How many merged values are processed between two progress reports.
+const PROGRESS_STEP: u64 = 1 << 20;
+
+#[derive(Clone, Debug, PartialEq)]
+/// Events reported to the progress callback of `ExternalEliasFanoBuilder`.
+pub enum ExternalBuilderProgress {
+    /// A sorted run of `values` elements was written to a temporary file.
+    RunSpilled {
+        run_index: usize,
+        values: u64,
+    },
+    /// `merged` values out of `total` were written to the output.
+    Merged {
+        merged: u64,
+        total: u64,
+    },
+}
+
+/// Builder that allows to create an EliasFano from an **unsorted** stream of
+/// values that might not fit in memory.
+///
+/// The values are buffered and once the buffer is full it's sorted and written
+/// to a temporary file (a run). At build time the runs are merged and the
+/// sorted values are streamed directly into the serialized EliasFano format
+/// so the full vector of values is never kept in memory. As in
+/// `EliasFano::from_vec_auto_min`, the smallest value is the minimum value.
+///
+/// ```
+/// # use elias_fano_rust::*;
+/// let mut builder = ExternalEliasFanoBuilder::new(std::env::temp_dir(), 2).unwrap();
+/// builder.extend(vec![15, 8, 32, 5, 8].into_iter()).unwrap();
+/// let ef = builder.build().unwrap();
+/// assert_eq!(ef, EliasFano::from_vec_auto_min(&[5_u64, 8, 8, 15, 32]).unwrap());
+/// ```
+pub struct ExternalEliasFanoBuilder {
+    temp_dir: PathBuf,
+    builder_id: u64,
+    buffer: Vec<u64>,
+    buffer_capacity: usize,
+    runs: Vec<PathBuf>,
+    number_of_elements: u64,
+    min_value: u64,
+    max_value: u64,
+    progress: Option<Box<dyn FnMut(ExternalBuilderProgress) + Send>>,
+}
+
+impl std::fmt::Debug for ExternalEliasFanoBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExternalEliasFanoBuilder")
+            .field("temp_dir", &self.temp_dir)
+            .field("buffer_capacity", &self.buffer_capacity)
+            .field("runs", &self.runs)
+            .field("number_of_elements", &self.number_of_elements)
+            .field("min_value", &self.min_value)
+            .field("max_value", &self.max_value)
+            .finish()
+    }
+}
+
+impl ExternalEliasFanoBuilder {
+    /// Create a new builder which writes its temporary files in `temp_dir`
+    /// and keeps at most `buffer_capacity` values in memory.
+    pub fn new(temp_dir: impl AsRef<Path>, buffer_capacity: usize) -> Result<ExternalEliasFanoBuilder, String> {
+        if buffer_capacity == 0 {
+            return Err("The buffer capacity of the external builder must be at least 1.".to_string());
+        }
+        let temp_dir = temp_dir.as_ref().to_path_buf();
+        if !temp_dir.is_dir() {
+            return Err(format!(
+                "The given temporary directory {} does not exist or it's not a directory.",
+                temp_dir.display()
+            ));
+        }
+        Ok(ExternalEliasFanoBuilder{
+            temp_dir,
+            builder_id: BUILDER_COUNTER.fetch_add(1, Ordering::SeqCst),
+            buffer: Vec::new(),
+            buffer_capacity,
+            runs: Vec::new(),
+            number_of_elements: 0,
+            min_value: u64::MAX,
+            max_value: 0,
+            progress: None,
+        })
+    }
+
+    /// Set a callback which will be called on every spilled run and
+    /// periodically during the merge.
+    pub fn with_progress(mut self, callback: impl FnMut(ExternalBuilderProgress) + Send + 'static) -> Self {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Return the number of values pushed so far.
+    pub fn len(&self) -> u64 {
+        self.number_of_elements
+    }
+
+    /// Return if no values were pushed so far.
+    pub fn is_empty(&self) -> bool {
+        self.number_of_elements == 0
+    }
+
+    /// Add a value, the values can be pushed in any order.
+    pub fn push(&mut self, value: u64) -> Result<(), String> {
+        if self.buffer.len() >= self.buffer_capacity {
+            self.spill()?;
+        }
+        self.buffer.push(value);
+        self.number_of_elements += 1;
+        self.min_value = self.min_value.min(value);
+        self.max_value = self.max_value.max(value);
+        Ok(())
+    }
+
+    /// Add all the values of the iterator, the values can be in any order.
+    pub fn extend(&mut self, values: impl Iterator<Item = u64>) -> Result<(), String> {
+        for value in values {
+            self.push(value)?;
+        }
+        Ok(())
+    }
+
+    /// Add all the values read from the given reader, which must contain
+    /// a sequence of little-endian u64 (e.g. a binary edge list on disk).
+    pub fn extend_from_reader(&mut self, reader: impl Read) -> Result<(), String> {
+        let mut reader = BufReader::new(reader);
+        let mut buffer = [0_u8; 8];
+        loop {
+            match read_exact_or_eof(&mut reader, &mut buffer)? {
+                true => self.push(u64::from_le_bytes(buffer))?,
+                false => return Ok(()),
+            }
+        }
+    }
+
+    fn report(&mut self, event: ExternalBuilderProgress) {
+        if let Some(callback) = &mut self.progress {
+            callback(event);
+        }
+    }
+
+    fn run_path(&self, run_index: usize) -> PathBuf {
+        self.temp_dir.join(format!(
+            "elias_fano_run_{}_{}_{}.bin",
+            std::process::id(), self.builder_id, run_index
+        ))
+    }
+
+    /// Sort the buffer and write it to a new temporary file.
+    fn spill(&mut self) -> Result<(), String> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        self.buffer.par_sort_unstable();
+
+        let run_index = self.runs.len();
+        let path = self.run_path(run_index);
+        let file = create_file(&path)?;
+        self.runs.push(path);
+
+        let mut writer = BufWriter::new(file);
+        for value in &self.buffer {
+            write_u64(&mut writer, *value)?;
+        }
+        writer.flush().map_err(|e| format!("Cannot write the run to disk: {}", e))?;
+
+        let values = self.buffer.len() as u64;
+        self.buffer.clear();
+        self.report(ExternalBuilderProgress::RunSpilled{run_index, values});
+        Ok(())
+    }
+
+    /// Return an iterator over all the values pushed in sorted order.
+    /// If nothing was spilled, the buffer is sorted in memory, otherwise
+    /// the runs are merged.
+    fn sorted_values(&mut self) -> Result<Box<dyn Iterator<Item = Result<u64, String>>>, String> {
+        if self.runs.is_empty() {
+            self.buffer.par_sort_unstable();
+            let buffer = std::mem::take(&mut self.buffer);
+            return Ok(Box::new(buffer.into_iter().map(Ok)));
+        }
+        self.spill()?;
+        // release the memory of the buffer before the merge
+        self.buffer = Vec::new();
+        Ok(Box::new(RunsMerger::new(&self.runs)?))
+    }
+
+    /// Return the smallest value pushed, which is used as the minimum value
+    /// of the EliasFano, or 0 if no value was pushed.
+    fn detected_min_value(&self) -> u64 {
+        self.min_value.min(self.max_value)
+    }
+
+    /// Merge the values and write the resulting EliasFano, in the same format
+    /// of `EliasFano::serialize`, to the given writer.
+    pub fn build_into(mut self, writer: impl Write) -> Result<(), String> {
+        let mut writer = BufWriter::new(writer);
+        let total = self.number_of_elements;
+        let universe = self.max_value;
+        let min_value = self.detected_min_value();
+        let low_bit_count = compute_low_bit_count(universe - min_value, total as usize)?;
+
+        // All the header fields are known in advance
+        write_u64(&mut writer, SERIALIZATION_MAGIC)?;
+        EliasFanoHeader{
+            universe,
+            min_value,
+            strict: false,
+            number_of_elements: total,
+            current_number_of_elements: total,
+            low_bit_count,
+            last_value: universe,
+        }.write(&mut writer)?;
+
+        // The low-bits are streamed directly in the output while the
+        // high-bits are written to a temporary file and appended later.
+        let low_bits_words = match total {
+            0 => 0,
+            _ => get_vec_size(low_bit_count, total as usize),
+        };
+        write_u64(&mut writer, low_bits_words)?;
+        let mut low_bits = PackedWriter::new(&mut writer);
+
+        // open the runs before registering the high-bits temporary file
+        let values = self.sorted_values()?;
+        let high_bits_path = self.run_path(usize::MAX);
+        let high_bits_file = create_file(&high_bits_path)?;
+        self.runs.push(high_bits_path.clone());
+        let mut high_bits = HighBitsWriter::new(BufWriter::new(high_bits_file));
+
+        let mut merged = 0;
+        for value in values {
+            let value = value? - min_value;
+            low_bits.push(value & shr(u64::MAX, 64 - low_bit_count), low_bit_count)?;
+            high_bits.push(shr(value, low_bit_count))?;
+            merged += 1;
+            if merged % PROGRESS_STEP == 0 {
+                self.report(ExternalBuilderProgress::Merged{merged, total});
+            }
+        }
+        low_bits.pad_to(low_bits_words)?;
+        let high_bits = high_bits.finish()?;
+
+        // Append the high-bits
+        write_u64(&mut writer, high_bits.len)?;
+        write_u64(&mut writer, high_bits.number_of_ones)?;
+        write_u64(&mut writer, high_bits.number_of_zeros)?;
+        write_u64(&mut writer, high_bits.words)?;
+        let mut reader = BufReader::new(
+            File::open(&high_bits_path)
+                .map_err(|e| format!("Cannot open the high-bits temporary file: {}", e))?
+        );
+        std::io::copy(&mut reader, &mut writer)
+            .map_err(|e| format!("Cannot copy the high-bits to the output: {}", e))?;
+        write_vec(&mut writer, &high_bits.index_zeros)?;
+        write_vec(&mut writer, &high_bits.index_ones)?;
+        writer.flush().map_err(|e| format!("Cannot flush the output: {}", e))?;
+
+        self.report(ExternalBuilderProgress::Merged{merged, total});
+        Ok(())
+    }
+
+    /// Merge the values and write the resulting EliasFano to the file at
+    /// the given path.
+    pub fn build_to_file(self, path: impl AsRef<Path>) -> Result<(), String> {
+        let file = create_file(path.as_ref())?;
+        self.build_into(file)
+    }
+
+    /// Merge the values and build the EliasFano in memory.
+    pub fn build(mut self) -> Result<EliasFano, String> {
+        let mut result = EliasFano::new_with_min_value(
+            self.detected_min_value(), self.max_value, self.number_of_elements as usize
+        )?;
+        let total = self.number_of_elements;
+        let mut merged = 0;
+        for value in self.sorted_values()? {
+            result.unchecked_push(value?);
+            merged += 1;
+            if merged % PROGRESS_STEP == 0 {
+                self.report(ExternalBuilderProgress::Merged{merged, total});
+            }
+        }
+        self.report(ExternalBuilderProgress::Merged{merged, total});
+        Ok(result)
+    }
+}
+
+impl Drop for ExternalEliasFanoBuilder {
+    fn drop(&mut self) {
+        for path in &self.runs {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+fn create_file(path: &Path) -> Result<File, String> {
+    OpenOptions::new().create(true).write(true).truncate(true).open(path)
+        .map_err(|e| format!("Cannot create the file {}: {}", path.display(), e))
+}
+
+/// Fill the buffer, returns false if the reader is at the end of the stream.
+fn read_exact_or_eof(reader: &mut impl Read, buffer: &mut [u8]) -> Result<bool, String> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {},
+            Err(e) => return Err(format!("Cannot read the values: {}", e)),
+        }
+    }
+    match filled {
+        0 => Ok(false),
+        n if n == buffer.len() => Ok(true),
+        n => Err(format!("The stream ended in the middle of a value ({} trailing bytes).", n)),
+    }
+}
+
+/// K-way merge of the sorted runs using a min-heap.
+struct RunsMerger {
+    readers: Vec<BufReader<File>>,
+    heap: BinaryHeap<Reverse<(u64, usize)>>,
+}
+
+impl RunsMerger {
+    fn new(runs: &[PathBuf]) -> Result<RunsMerger, String> {
+        let mut readers = Vec::with_capacity(runs.len());
+        let mut heap = BinaryHeap::with_capacity(runs.len());
+        for (run_index, path) in runs.iter().enumerate() {
+            let file = File::open(path)
+                .map_err(|e| format!("Cannot open the run {}: {}", path.display(), e))?;
+            let mut reader = BufReader::new(file);
+            let mut buffer = [0_u8; 8];
+            if read_exact_or_eof(&mut reader, &mut buffer)? {
+                heap.push(Reverse((u64::from_le_bytes(buffer), run_index)));
+            }
+            readers.push(reader);
+        }
+        Ok(RunsMerger{readers, heap})
+    }
+}
+
+impl Iterator for RunsMerger {
+    type Item = Result<u64, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((value, run_index)) = self.heap.pop()?;
+        let mut buffer = [0_u8; 8];
+        match read_exact_or_eof(&mut self.readers[run_index], &mut buffer) {
+            Ok(true) => self.heap.push(Reverse((u64::from_le_bytes(buffer), run_index))),
+            Ok(false) => {},
+            Err(e) => return Some(Err(e)),
+        }
+        Some(Ok(value))
+    }
+}
+
+/// Pack fixed-width values in little-endian words, with the same layout
+/// used by `safe_write`, and write the words as soon as they are complete.
+struct PackedWriter<W: Write> {
+    writer: W,
+    word: u64,
+    bits_in_word: u64,
+    words_written: u64,
+}
+
+impl<W: Write> PackedWriter<W> {
+    fn new(writer: W) -> PackedWriter<W> {
+        PackedWriter{
+            writer,
+            word: 0,
+            bits_in_word: 0,
+            words_written: 0,
+        }
+    }
+
+    fn push(&mut self, value: u64, value_size: u64) -> Result<(), String> {
+        if value_size == 0 {
+            return Ok(());
+        }
+        self.word |= shl(value, self.bits_in_word);
+        self.bits_in_word += value_size;
+        if self.bits_in_word >= WORD_SIZE {
+            write_u64(&mut self.writer, self.word)?;
+            self.words_written += 1;
+            self.bits_in_word -= WORD_SIZE;
+            self.word = shr(value, value_size - self.bits_in_word);
+        }
+        Ok(())
+    }
+
+    /// Flush the partial word and write zeros up to the given number of words.
+    fn pad_to(mut self, words: u64) -> Result<u64, String> {
+        if self.bits_in_word > 0 {
+            write_u64(&mut self.writer, self.word)?;
+            self.words_written += 1;
+        }
+        while self.words_written < words {
+            write_u64(&mut self.writer, 0)?;
+            self.words_written += 1;
+        }
+        self.writer.flush().map_err(|e| format!("Cannot flush the packed values: {}", e))?;
+        Ok(self.words_written)
+    }
+}
+
+/// The metadata of the high-bits collected while streaming them.
+struct HighBitsMetadata {
+    len: u64,
+    words: u64,
+    number_of_ones: u64,
+    number_of_zeros: u64,
+    index_zeros: Vec<u64>,
+    index_ones: Vec<u64>,
+}
+
+/// Write the high-bits in inverted unary code, building the SimpleSelect
+/// indices exactly as `SimpleSelect::push` would.
+struct HighBitsWriter<W: Write> {
+    bits: PackedWriter<W>,
+    last_high_value: u64,
+    metadata: HighBitsMetadata,
+}
+
+impl<W: Write> HighBitsWriter<W> {
+    fn new(writer: W) -> HighBitsWriter<W> {
+        HighBitsWriter{
+            bits: PackedWriter::new(writer),
+            last_high_value: 0,
+            metadata: HighBitsMetadata{
+                len: 0,
+                words: 0,
+                number_of_ones: 0,
+                number_of_zeros: 0,
+                index_zeros: Vec::new(),
+                index_ones: Vec::new(),
+            },
+        }
+    }
+
+    fn push(&mut self, high: u64) -> Result<(), String> {
+        for _ in self.last_high_value..high {
+            if self.metadata.number_of_zeros & INDEX_MASK == 0 {
+                self.metadata.index_zeros.push(self.metadata.len);
+            }
+            self.metadata.number_of_zeros += 1;
+            self.metadata.len += 1;
+            self.bits.push(0, 1)?;
+        }
+        if self.metadata.number_of_ones & INDEX_MASK == 0 {
+            self.metadata.index_ones.push(self.metadata.len);
+        }
+        self.metadata.number_of_ones += 1;
+        self.metadata.len += 1;
+        self.bits.push(1, 1)?;
+        self.last_high_value = high;
+        Ok(())
+    }
+
+    fn finish(self) -> Result<HighBitsMetadata, String> {
+        let HighBitsWriter{bits, mut metadata, ..} = self;
+        metadata.words = bits.pad_to(0)?;
+        Ok(metadata)
+    }
+}
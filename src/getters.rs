@@ -1,9 +1,7 @@
 use super::*;
-use rayon::iter::{ParallelIterator, IndexedParallelIterator};
-use rayon::prelude::*;
-use std::ops::Range;
+use core::ops::Range;
 
-impl EliasFano {
+impl<T: UnsignedInteger> EliasFano<T> {
     #[inline]
     pub fn len(&self) -> usize {
         self.current_number_of_elements as usize
@@ -16,7 +14,51 @@ impl EliasFano {
 
     /// Return universe of the elias fano data structure.
     #[inline]
-    pub fn get_universe(&self) -> u64 {
+    pub fn get_universe(&self) -> T {
         self.universe
     }
+
+    /// Return if the elias fano is in strict mode, so it stores strictly
+    /// increasing values encoded as `value - index`.
+    #[inline]
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Return the minimum value that can be stored in the elias fano,
+    /// the values are encoded relatively to it.
+    #[inline]
+    pub fn get_min_value(&self) -> T {
+        self.min_value
+    }
+
+    /// Return the number of elements the elias fano was created for.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.number_of_elements as usize
+    }
+
+    /// Return the number of bits of the low part of each value.
+    #[inline]
+    pub fn low_bit_count(&self) -> u64 {
+        self.low_bit_count
+    }
+
+    /// Return the words of the packed low parts of the values.
+    #[inline]
+    pub fn low_bits(&self) -> &[u64] {
+        &self.low_bits
+    }
+
+    /// Return the bitvector with the high parts of the values in unary code.
+    #[inline]
+    pub fn high_bits(&self) -> &SimpleSelect {
+        &self.high_bits
+    }
+
+    /// Return the words of the bitvector of the high parts.
+    #[inline]
+    pub fn high_bits_words(&self) -> &[u64] {
+        self.high_bits.high_bits_words()
+    }
 }
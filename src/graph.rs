@@ -0,0 +1,126 @@
+use super::*;
+use rayon::iter::IndexedParallelIterator;
+use std::io::{Read, Write};
+
+#[derive(Clone, Debug, PartialEq)]
+/// Compressed adjacency of a directed graph, the edges are stored
+/// in a single EliasFano as the values `src * number_of_nodes + dst`
+/// so the neighbours of a node are a contiguous range of values.
+///
+/// ```
+/// # use elias_fano_rust::EliasFanoGraph;
+/// let edges = [(0, 1), (0, 3), (2, 0), (3, 3)];
+/// let graph = EliasFanoGraph::from_vec(&edges, 4).unwrap();
+/// assert_eq!(graph.neighbours(0).collect::<Vec<_>>(), vec![1, 3]);
+/// assert_eq!(graph.degree(1), 0);
+/// assert!(graph.has_edge(2, 0));
+/// assert!(!graph.has_edge(0, 2));
+/// ```
+pub struct EliasFanoGraph {
+    edges: PairEliasFano<MulPacker>,
+}
+
+impl EliasFanoGraph {
+    /// Create a graph from an iterator of edges **sorted** by source
+    /// and then by destination.
+    ///
+    /// # Arguments
+    ///
+    /// * edges: impl Iterator<Item = (u64, u64)> - Iterator of the sorted edges.
+    /// * number_of_nodes: u64 - The number of nodes, all the nodes must be smaller than it.
+    /// * number_of_edges: usize - The number of edges in the iterator.
+    pub fn from_edge_iter(
+        edges: impl Iterator<Item = (u64, u64)>,
+        number_of_nodes: u64,
+        number_of_edges: usize,
+    ) -> Result<EliasFanoGraph, String> {
+        Ok(EliasFanoGraph{
+            edges: PairEliasFano::from_iter(
+                edges,
+                MulPacker::new(number_of_nodes, number_of_nodes)?,
+                number_of_edges,
+            )?,
+        })
+    }
+
+    /// Create a graph from a slice of edges **sorted** by source
+    /// and then by destination.
+    ///
+    /// # Arguments
+    ///
+    /// * edges: &[(u64, u64)] - The sorted edges.
+    /// * number_of_nodes: u64 - The number of nodes, all the nodes must be smaller than it.
+    pub fn from_vec(edges: &[(u64, u64)], number_of_nodes: u64) -> Result<EliasFanoGraph, String> {
+        EliasFanoGraph::from_edge_iter(edges.iter().cloned(), number_of_nodes, edges.len())
+    }
+
+    #[inline]
+    pub fn get_number_of_nodes(&self) -> u64 {
+        self.edges.get_packer().get_number_of_firsts()
+    }
+
+    #[inline]
+    pub fn get_number_of_edges(&self) -> u64 {
+        self.edges.len() as u64
+    }
+
+    /// Return the underlying EliasFano with the encoded edges.
+    #[inline]
+    pub fn as_elias_fano(&self) -> &EliasFano<u64> {
+        self.edges.as_elias_fano()
+    }
+
+    /// Return an iterator over the destinations of the edges
+    /// starting from `src`, in increasing order.
+    #[inline]
+    pub fn neighbours(&self, src: u64) -> impl Iterator<Item = u64> + '_ {
+        self.edges.iter_with_first(src).map(|(_, dst)| dst)
+    }
+
+    /// Return the number of edges starting from `src`.
+    #[inline]
+    pub fn degree(&self, src: u64) -> u64 {
+        self.edges.count_with_first(src)
+    }
+
+    /// Return if the graph contains the edge from `src` to `dst`.
+    #[inline]
+    pub fn has_edge(&self, src: u64, dst: u64) -> bool {
+        self.edges.contains(src, dst)
+    }
+
+    /// Return an iterator over the edges as `(src, dst)`.
+    #[inline]
+    pub fn iter_edges(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        self.edges.iter()
+    }
+
+    /// Return a parallel iterator over the edges as `(src, dst)`.
+    #[inline]
+    pub fn edges(&self) -> impl IndexedParallelIterator<Item = (u64, u64)> + '_ {
+        self.edges.par_iter()
+    }
+
+    /// Return the memory used in bytes
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.edges.size()
+    }
+
+    /// Write the graph to the given writer, the format is the number
+    /// of nodes as a little-endian u64 followed by the serialized EliasFano.
+    pub fn serialize(&self, writer: &mut impl Write) -> Result<(), String> {
+        write_u64(writer, self.get_number_of_nodes())?;
+        self.as_elias_fano().serialize(writer)
+    }
+
+    /// Read a graph written by `serialize`.
+    pub fn deserialize(reader: &mut impl Read) -> Result<EliasFanoGraph, String> {
+        let number_of_nodes = read_u64(reader)?;
+        let packer = MulPacker::new(number_of_nodes, number_of_nodes)?;
+        let edges = EliasFano::<u64>::deserialize(reader)?;
+        Ok(EliasFanoGraph{
+            edges: PairEliasFano::from_elias_fano(edges, packer)?,
+        })
+    }
+}
@@ -1,7 +1,7 @@
 use super::*;
-use std::hash::{Hash, Hasher};
+use core::hash::{Hash, Hasher};
 
-impl Hash for EliasFano {
+impl<T: UnsignedInteger> Hash for EliasFano<T> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.iter().for_each(|x| x.hash(state));
     }
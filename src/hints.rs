@@ -0,0 +1,49 @@
+//! Branch prediction and prefetching hints.
+//!
+//! With the `nightly` feature the branch hints are the compiler intrinsics,
+//! otherwise the unlikely branch calls an empty `#[cold]` function which
+//! gives the same hint to the optimizer on stable Rust.
+
+#[cfg(feature = "nightly")]
+pub(crate) use core::intrinsics::{likely, unlikely};
+
+#[cfg(not(feature = "nightly"))]
+#[cold]
+#[inline(always)]
+fn cold_path() {}
+
+/// Hint that the condition is usually true.
+#[cfg(not(feature = "nightly"))]
+#[inline(always)]
+#[allow(dead_code)]
+pub(crate) fn likely(condition: bool) -> bool {
+    if !condition {
+        cold_path();
+    }
+    condition
+}
+
+/// Hint that the condition is usually false.
+#[cfg(not(feature = "nightly"))]
+#[inline(always)]
+pub(crate) fn unlikely(condition: bool) -> bool {
+    if condition {
+        cold_path();
+    }
+    condition
+}
+
+/// Hint the CPU to load in cache the `index`-th element of `data`.
+/// This is a no-op on the architectures without a prefetch instruction.
+#[inline(always)]
+#[allow(unused_variables)]
+pub(crate) fn prefetch<T>(data: &[T], index: usize) {
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        use core::arch::x86_64::{_mm_prefetch, _MM_HINT_T0};
+        // prefetching an invalid address does not fault, so there is no need
+        // to check the bounds, but the pointer arithmetic must not overflow
+        let pointer = data.as_ptr().wrapping_add(index);
+        _mm_prefetch::<_MM_HINT_T0>(pointer as *const i8);
+    }
+}
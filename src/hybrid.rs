@@ -0,0 +1,291 @@
+use super::*;
+use crate::traits::SelectZero;
+
+#[derive(Clone, Debug, PartialEq)]
+/// Set of distinct u64 in [0, `universe`] stored with the smallest of
+/// the available representations, chosen with the estimates of their sizes:
+///
+/// * `Sparse`: a strict EliasFano of the values, good for sparse sets.
+/// * `Dense`: a SimpleSelect bitmap with a bit for each value of the universe.
+/// * `Complement`: a strict EliasFano of the values **not** in the set, good
+///   for sets which contain almost all the universe.
+///
+/// ```
+/// # use elias_fano_rust::HybridSet;
+/// let sparse = HybridSet::from_vec(&[1, 1_000, 1_000_000]).unwrap();
+/// assert!(matches!(sparse, HybridSet::Sparse(_)));
+///
+/// let values = (0..10_000).filter(|x| x % 2 == 0).collect::<Vec<u64>>();
+/// let dense = HybridSet::from_vec(&values).unwrap();
+/// assert!(matches!(dense, HybridSet::Dense(_)));
+///
+/// let values = (0..10_000).filter(|x| *x != 5_000).collect::<Vec<u64>>();
+/// let complement = HybridSet::from_vec(&values).unwrap();
+/// assert!(matches!(complement, HybridSet::Complement{..}));
+/// assert!(!complement.contains(5_000));
+/// assert_eq!(complement.rank(6_000), 5_999);
+/// assert_eq!(complement.select(5_000), Some(5_001));
+/// ```
+pub enum HybridSet {
+    Sparse(EliasFano<u64>),
+    Dense(SimpleSelect),
+    Complement{
+        absent: EliasFano<u64>,
+        universe: u64,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The representations which can be used by `HybridSet`.
+pub enum HybridSetKind {
+    Sparse,
+    Dense,
+    Complement,
+}
+
+/// Return the universe of a strict EliasFano with the given number of elements,
+/// which saturates if there are more elements than the values in the universe.
+#[inline]
+fn strict_universe(universe: u64, number_of_elements: usize) -> u64 {
+    universe.saturating_sub((number_of_elements as u64).saturating_sub(1))
+}
+
+impl HybridSet {
+    /// Return the estimated memory in bytes of each representation of a set
+    /// of `number_of_elements` values in [0, `universe`]. The representations
+    /// which cannot be used have no estimate.
+    pub fn estimate_sizes(universe: u64, number_of_elements: usize) -> Vec<(HybridSetKind, usize)> {
+        let mut result = vec![(
+            HybridSetKind::Sparse,
+            EliasFano::<u64>::estimate_size(strict_universe(universe, number_of_elements), number_of_elements),
+        )];
+        // the bitmap and the complement need the number of values in the universe
+        if universe < u64::MAX && (number_of_elements as u64) <= universe + 1 {
+            let number_of_absents = (universe + 1 - number_of_elements as u64) as usize;
+            result.push((
+                HybridSetKind::Dense,
+                SimpleSelect::estimate_size(universe + 1, number_of_elements as u64),
+            ));
+            result.push((
+                HybridSetKind::Complement,
+                EliasFano::<u64>::estimate_size(strict_universe(universe, number_of_absents), number_of_absents),
+            ));
+        }
+        result
+    }
+
+    /// Return the representation with the smallest estimated size.
+    pub fn choose_kind(universe: u64, number_of_elements: usize) -> HybridSetKind {
+        HybridSet::estimate_sizes(universe, number_of_elements).into_iter()
+            .min_by_key(|(_, size)| *size)
+            .map(|(kind, _)| kind)
+            .unwrap()
+    }
+
+    /// Create a set from an iterator of **strictly increasing** values in [0, `universe`]
+    /// with the representation with the smallest estimated size.
+    ///
+    /// # Arguments
+    ///
+    /// * values: impl Iterator<Item = u64> - Iterator of strictly increasing values.
+    /// * universe: u64 - The maximum value that can be in the set.
+    /// * number_of_elements: usize - The number of values in the iterator.
+    pub fn from_iter(
+        values: impl Iterator<Item = u64>,
+        universe: u64,
+        number_of_elements: usize,
+    ) -> Result<HybridSet, String> {
+        if number_of_elements as u128 > universe as u128 + 1 {
+            return Err(format!(
+                "There cannot be {} distinct values in [0, {}].",
+                number_of_elements, universe
+            ));
+        }
+        let kind = HybridSet::choose_kind(universe, number_of_elements);
+        HybridSet::from_iter_with_kind(values, universe, number_of_elements, kind)
+    }
+
+    /// Create a set from a slice of **strictly increasing** values, the universe
+    /// is the last value.
+    pub fn from_vec(values: &[u64]) -> Result<HybridSet, String> {
+        HybridSet::from_iter(values.iter().cloned(), *values.last().unwrap_or(&0), values.len())
+    }
+
+    /// Create a set from an iterator of **strictly increasing** values in [0, `universe`]
+    /// with the given representation.
+    pub fn from_iter_with_kind(
+        values: impl Iterator<Item = u64>,
+        universe: u64,
+        number_of_elements: usize,
+        kind: HybridSetKind,
+    ) -> Result<HybridSet, String> {
+        let mut count = 0;
+        let mut next_value = 0;
+        let mut result = match kind {
+            HybridSetKind::Sparse => HybridSet::Sparse(
+                EliasFano::new_strict(0, universe, number_of_elements)?
+            ),
+            HybridSetKind::Dense | HybridSetKind::Complement if universe == u64::MAX => {
+                return Err(format!(
+                    "The {:?} representation does not support the universe {}.",
+                    kind, universe
+                ));
+            }
+            HybridSetKind::Dense => HybridSet::Dense(
+                SimpleSelect::with_capacity(universe as usize + 1)
+            ),
+            HybridSetKind::Complement => {
+                let number_of_absents = (universe + 1).checked_sub(number_of_elements as u64)
+                    .ok_or_else(|| format!(
+                        "There cannot be {} distinct values in [0, {}].",
+                        number_of_elements, universe
+                    ))? as usize;
+                HybridSet::Complement{
+                    absent: EliasFano::new_strict(0, universe, number_of_absents)?,
+                    universe,
+                }
+            }
+        };
+
+        for value in values {
+            if value > universe || value < next_value {
+                return Err(format!(
+                    concat!(
+                        "The value {} is not valid, the values must be strictly ",
+                        "increasing and at most the universe {}."
+                    ),
+                    value, universe
+                ));
+            }
+            match &mut result {
+                HybridSet::Sparse(ef) => ef.push(value)?,
+                HybridSet::Dense(bitmap) => {
+                    for _ in next_value..value {
+                        bitmap.push(false);
+                    }
+                    bitmap.push(true);
+                }
+                HybridSet::Complement{absent, ..} => {
+                    for absent_value in next_value..value {
+                        absent.push(absent_value)?;
+                    }
+                }
+            }
+            next_value = value + 1;
+            count += 1;
+        }
+
+        if count != number_of_elements {
+            return Err(format!(
+                "The iterator yielded {} values but {} were expected.",
+                count, number_of_elements
+            ));
+        }
+
+        // add the values after the last one
+        match &mut result {
+            HybridSet::Dense(bitmap) => {
+                for _ in next_value..=universe {
+                    bitmap.push(false);
+                }
+                bitmap.shrink_to_fit();
+            }
+            HybridSet::Complement{absent, ..} => {
+                for absent_value in next_value..=universe {
+                    absent.push(absent_value)?;
+                }
+                absent.shrink_to_fit();
+            }
+            HybridSet::Sparse(ef) => ef.shrink_to_fit(),
+        }
+
+        Ok(result)
+    }
+
+    /// Return the representation used.
+    #[inline]
+    pub fn kind(&self) -> HybridSetKind {
+        match self {
+            HybridSet::Sparse(_) => HybridSetKind::Sparse,
+            HybridSet::Dense(_) => HybridSetKind::Dense,
+            HybridSet::Complement{..} => HybridSetKind::Complement,
+        }
+    }
+
+    /// Return the number of values in the set.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        match self {
+            HybridSet::Sparse(ef) => ef.len() as u64,
+            HybridSet::Dense(bitmap) => bitmap.count_ones(),
+            HybridSet::Complement{absent, universe} => universe + 1 - absent.len() as u64,
+        }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub fn contains(&self, value: u64) -> bool {
+        match self {
+            HybridSet::Sparse(ef) => ef.contains(value),
+            HybridSet::Dense(bitmap) => value < bitmap.len() && bitmap.unchecked_get(value),
+            HybridSet::Complement{absent, universe} => value <= *universe && !absent.contains(value),
+        }
+    }
+
+    /// Return the number of values in the set which are smaller than `value`.
+    #[inline]
+    pub fn rank(&self, value: u64) -> u64 {
+        match self {
+            HybridSet::Sparse(ef) => ef.unchecked_rank(value),
+            HybridSet::Dense(bitmap) => bitmap.rank1(value),
+            HybridSet::Complement{absent, universe} => {
+                value.min(universe + 1) - absent.unchecked_rank(value)
+            }
+        }
+    }
+
+    /// Return the value of the chosen index, if present.
+    #[inline]
+    pub fn select(&self, index: u64) -> Option<u64> {
+        if index >= self.len() {
+            return None;
+        }
+        Some(match self {
+            HybridSet::Sparse(ef) => ef.unchecked_select(index),
+            HybridSet::Dense(bitmap) => bitmap.unchecked_select1(index),
+            HybridSet::Complement{absent, ..} => absent.select0(index),
+        })
+    }
+
+    /// Return an iterator over the values in the set.
+    pub fn iter(&self) -> Box<dyn Iterator<Item = u64> + '_> {
+        match self {
+            HybridSet::Sparse(ef) => Box::new(ef.iter()),
+            HybridSet::Dense(bitmap) => Box::new(bitmap.iter()),
+            HybridSet::Complement{absent, universe} => {
+                let mut absent = absent.iter().peekable();
+                Box::new((0..=*universe).filter(move |value| {
+                    if absent.peek() == Some(value) {
+                        absent.next();
+                        false
+                    } else {
+                        true
+                    }
+                }))
+            }
+        }
+    }
+
+    /// Return the memory used in bytes
+    pub fn size(&self) -> usize {
+        match self {
+            HybridSet::Sparse(ef) => ef.size(),
+            HybridSet::Dense(bitmap) => bitmap.size().total(),
+            HybridSet::Complement{absent, ..} => absent.size() + core::mem::size_of::<u64>(),
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use super::*;
+use core::fmt::{Debug, Display};
+use core::hash::Hash;
+use core::ops::{Add, BitAnd, BitOr, Not, Sub};
+use core::sync::atomic::AtomicU64;
+
+/// Trait for the unsigned integer types that can be stored in EliasFano.
+///
+/// The high-bits are always decoded as u64 (they are bounded by twice the
+/// number of elements), while the low-bits have the type of the values
+/// and are packed in words of 64 bits. Types wider than 64 bits, like u128,
+/// split their low-bits across multiple words.
+pub trait UnsignedInteger:
+    Copy + Default + Debug + Display + Hash
+    + PartialEq + Eq + PartialOrd + Ord
+    + Send + Sync + 'static
+    + Add<Output = Self> + Sub<Output = Self>
+    + BitAnd<Output = Self> + BitOr<Output = Self> + Not<Output = Self>
+{
+    /// Number of bits of the type.
+    const BITS: u64;
+    const ZERO: Self;
+    const ONE: Self;
+    const MAX: Self;
+
+    /// Convert from u64, truncating if the type is smaller.
+    fn from_u64(value: u64) -> Self;
+    /// Convert to u64, truncating if the type is bigger.
+    fn to_u64(self) -> u64;
+    /// Convert from u128, truncating if the type is smaller.
+    fn from_u128(value: u128) -> Self;
+    /// Convert to u128.
+    fn to_u128(self) -> u128;
+    /// Convert to f64, this is used to compute the number of low bits.
+    fn to_f64(self) -> f64;
+
+    /// Left shift which returns 0 if the offset is bigger than the type.
+    fn shift_left(self, offset: u64) -> Self;
+    /// Right shift which returns 0 if the offset is bigger than the type.
+    fn shift_right(self, offset: u64) -> Self;
+
+    /// Return a value with the lowest `bits` bits set to one.
+    #[inline(always)]
+    fn mask(bits: u64) -> Self {
+        Self::MAX.shift_right(Self::BITS - bits)
+    }
+
+    /// Read the `index`-th value of `value_size` bits from the packed array.
+    #[inline(always)]
+    fn read_packed(array: &[u64], index: u64, value_size: u64) -> Self {
+        Self::from_u64(read_bits(array, index * value_size, value_size))
+    }
+
+    /// Write the `index`-th value of `value_size` bits in the packed array.
+    #[inline(always)]
+    fn write_packed(array: &mut [u64], index: u64, value: Self, value_size: u64) {
+        write_bits(array, index * value_size, value.to_u64(), value_size)
+    }
+
+    /// Concurrently write the `index`-th value of `value_size` bits in the packed array.
+    #[inline(always)]
+    fn concurrent_write_packed(array: &[AtomicU64], index: u64, value: Self, value_size: u64) {
+        concurrent_write_bits(array, index * value_size, value.to_u64(), value_size)
+    }
+}
+
+macro_rules! impl_unsigned_integer {
+    ($($ty:ty),*) => {$(
+        impl UnsignedInteger for $ty {
+            const BITS: u64 = (8 * core::mem::size_of::<$ty>()) as u64;
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const MAX: Self = <$ty>::MAX;
+
+            #[inline(always)]
+            fn from_u64(value: u64) -> Self {
+                value as $ty
+            }
+
+            #[inline(always)]
+            fn to_u64(self) -> u64 {
+                self as u64
+            }
+
+            #[inline(always)]
+            fn from_u128(value: u128) -> Self {
+                value as $ty
+            }
+
+            #[inline(always)]
+            fn to_u128(self) -> u128 {
+                self as u128
+            }
+
+            #[inline(always)]
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            #[inline(always)]
+            fn shift_left(self, offset: u64) -> Self {
+                self.checked_shl(offset as u32).unwrap_or(0)
+            }
+
+            #[inline(always)]
+            fn shift_right(self, offset: u64) -> Self {
+                self.checked_shr(offset as u32).unwrap_or(0)
+            }
+        }
+    )*};
+}
+
+impl_unsigned_integer!(u8, u16, u32, u64, usize);
+
+impl UnsignedInteger for u128 {
+    const BITS: u64 = 128;
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+    const MAX: Self = u128::MAX;
+
+    #[inline(always)]
+    fn from_u64(value: u64) -> Self {
+        value as u128
+    }
+
+    #[inline(always)]
+    fn to_u64(self) -> u64 {
+        self as u64
+    }
+
+    #[inline(always)]
+    fn from_u128(value: u128) -> Self {
+        value
+    }
+
+    #[inline(always)]
+    fn to_u128(self) -> u128 {
+        self
+    }
+
+    #[inline(always)]
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    #[inline(always)]
+    fn shift_left(self, offset: u64) -> Self {
+        self.checked_shl(offset as u32).unwrap_or(0)
+    }
+
+    #[inline(always)]
+    fn shift_right(self, offset: u64) -> Self {
+        self.checked_shr(offset as u32).unwrap_or(0)
+    }
+
+    /// The values with more than 64 bits are split in two parts,
+    /// the lowest 64 bits followed by the remaining ones.
+    #[inline(always)]
+    fn read_packed(array: &[u64], index: u64, value_size: u64) -> Self {
+        let pos = index * value_size;
+        if value_size <= WORD_SIZE {
+            return read_bits(array, pos, value_size) as u128;
+        }
+        let lower = read_bits(array, pos, WORD_SIZE) as u128;
+        let higher = read_bits(array, pos + WORD_SIZE, value_size - WORD_SIZE) as u128;
+        (higher << 64) | lower
+    }
+
+    #[inline(always)]
+    fn write_packed(array: &mut [u64], index: u64, value: Self, value_size: u64) {
+        let pos = index * value_size;
+        if value_size <= WORD_SIZE {
+            return write_bits(array, pos, value as u64, value_size);
+        }
+        write_bits(array, pos, value as u64, WORD_SIZE);
+        write_bits(array, pos + WORD_SIZE, (value >> 64) as u64, value_size - WORD_SIZE);
+    }
+
+    #[inline(always)]
+    fn concurrent_write_packed(array: &[AtomicU64], index: u64, value: Self, value_size: u64) {
+        let pos = index * value_size;
+        if value_size <= WORD_SIZE {
+            return concurrent_write_bits(array, pos, value as u64, value_size);
+        }
+        concurrent_write_bits(array, pos, value as u64, WORD_SIZE);
+        concurrent_write_bits(array, pos + WORD_SIZE, (value >> 64) as u64, value_size - WORD_SIZE);
+    }
+}
@@ -0,0 +1,349 @@
+use super::*;
+use codes::{BitReader, BitWriter};
+
+#[derive(Clone, Debug, PartialEq)]
+/// Binary interpolative coding of a sorted sequence of u64.
+///
+/// The first and the last value are stored explicitly, then the middle
+/// value is encoded with the minimal binary code in the range allowed
+/// by its neighbours and the two halves are encoded recursively.
+/// Dense clusters of strictly increasing values take no bits at all,
+/// so for short and clustered sequences this is much smaller than EliasFano,
+/// at the cost of a select which needs to decode the values before the chosen one.
+///
+/// ```
+/// # use elias_fano_rust::{EliasFano, InterpolativeSequence, SortedSequence};
+/// let values = [3, 4, 5, 6, 7, 100, 101, 102];
+/// let sequence = InterpolativeSequence::from_vec(&values).unwrap();
+/// assert_eq!(sequence.select(5).unwrap(), 100);
+/// assert_eq!(sequence.decode(), values);
+/// assert!(sequence.size() < EliasFano::from_vec(&values).unwrap().size());
+/// ```
+pub struct InterpolativeSequence {
+    data: Vec<u64>,
+    number_of_bits: u64,
+    number_of_elements: usize,
+    first_value: u64,
+    last_value: u64,
+    strict: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+/// The elements in `[start, end)` of a sequence, whose values are all in `[low, high]`.
+struct Subsequence {
+    start: usize,
+    end: usize,
+    low: u64,
+    high: u64,
+}
+
+impl Subsequence {
+    #[inline(always)]
+    fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    /// Return the index of the element which is encoded first.
+    #[inline(always)]
+    fn middle(&self) -> usize {
+        self.start + (self.end - self.start) / 2
+    }
+
+    /// Return the smallest and biggest value possible for the middle element.
+    #[inline(always)]
+    fn middle_bounds(&self, strict: bool) -> (u64, u64) {
+        match strict {
+            true => (
+                self.low + (self.middle() - self.start) as u64,
+                self.high - (self.end - 1 - self.middle()) as u64,
+            ),
+            false => (self.low, self.high),
+        }
+    }
+
+    /// Return the elements before and after the middle one, which has the given value.
+    #[inline(always)]
+    fn split(&self, strict: bool, value: u64) -> (Subsequence, Subsequence) {
+        let (left_high, right_low) = match strict {
+            true => (value.wrapping_sub(1), value + 1),
+            false => (value, value),
+        };
+        (
+            Subsequence{start: self.start, end: self.middle(), low: self.low, high: left_high},
+            Subsequence{start: self.middle() + 1, end: self.end, low: right_low, high: self.high},
+        )
+    }
+}
+
+impl InterpolativeSequence {
+    /// Encode a slice of **sorted** values, if they are strictly increasing
+    /// the encoding exploits it.
+    pub fn from_vec(values: &[u64]) -> Result<InterpolativeSequence, String> {
+        let mut strict = true;
+        for (index, window) in values.windows(2).enumerate() {
+            if window[0] > window[1] {
+                return Err(format!(
+                    concat!(
+                        "Cannot encode an unsorted set of values! ",
+                        "The value at index {} is {} but the next one is {}.",
+                    ),
+                    index, window[0], window[1]
+                ));
+            }
+            strict &= window[0] < window[1];
+        }
+
+        let mut result = InterpolativeSequence{
+            data: Vec::new(),
+            number_of_bits: 0,
+            number_of_elements: values.len(),
+            first_value: *values.first().unwrap_or(&0),
+            last_value: *values.last().unwrap_or(&0),
+            strict,
+        };
+
+        if values.len() > 2 {
+            let mut writer = BitWriter::new();
+            result.encode(&mut writer, values, result.inner());
+            let (mut data, number_of_bits) = writer.into_inner();
+            data.shrink_to_fit();
+            result.data = data;
+            result.number_of_bits = number_of_bits;
+        }
+
+        Ok(result)
+    }
+
+    /// Return if the values are strictly increasing.
+    #[inline]
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Return the elements between the first and the last, which are the encoded ones.
+    #[inline]
+    fn inner(&self) -> Subsequence {
+        let (low, high) = match self.strict {
+            true => (self.first_value + 1, self.last_value - 1),
+            false => (self.first_value, self.last_value),
+        };
+        Subsequence{start: 1, end: self.number_of_elements - 1, low, high}
+    }
+
+    /// Encode the values of the given subsequence.
+    fn encode(&self, writer: &mut BitWriter, values: &[u64], range: Subsequence) {
+        if range.is_empty() {
+            return;
+        }
+        let value = values[range.middle()];
+        let (lower, upper) = range.middle_bounds(self.strict);
+        writer.write_minimal_binary((value - lower) as u128, (upper - lower) as u128 + 1);
+
+        let (left, right) = range.split(self.strict, value);
+        self.encode(writer, values, left);
+        self.encode(writer, values, right);
+    }
+
+    /// Decode the values of the given subsequence and write them in `result`.
+    /// If `target` is in the subsequence, the decoding stops as soon as
+    /// it's found and its value is returned.
+    fn decode_range(
+        &self,
+        reader: &mut BitReader,
+        result: &mut Option<&mut [u64]>,
+        target: Option<usize>,
+        range: Subsequence,
+    ) -> Result<Option<u64>, String> {
+        if range.is_empty() {
+            return Ok(None);
+        }
+        let middle = range.middle();
+        let (lower, upper) = range.middle_bounds(self.strict);
+        let value = lower + reader.read_minimal_binary((upper - lower) as u128 + 1)? as u64;
+        if let Some(result) = result {
+            result[middle] = value;
+        }
+
+        let (left, right) = range.split(self.strict, value);
+        match target {
+            Some(target) if target == middle => Ok(Some(value)),
+            Some(target) if target < middle => {
+                self.decode_range(reader, result, Some(target), left)
+            }
+            _ => {
+                self.decode_range(reader, result, None, left)?;
+                self.decode_range(reader, result, target, right)
+            }
+        }
+    }
+
+    /// Return the smallest value of the given subsequence which is greater
+    /// or equal to `value`. The values before the middle one are only
+    /// decoded to skip them if the middle one is smaller than `value`,
+    /// and the values after it are never decoded if it's not.
+    fn next_geq_range(
+        &self,
+        reader: &mut BitReader,
+        value: u64,
+        range: Subsequence,
+    ) -> Result<Option<u64>, String> {
+        if range.is_empty() {
+            return Ok(None);
+        }
+        let (lower, upper) = range.middle_bounds(self.strict);
+        let middle_value = lower + reader.read_minimal_binary((upper - lower) as u128 + 1)? as u64;
+
+        let (left, right) = range.split(self.strict, middle_value);
+        if middle_value < value {
+            self.decode_range(reader, &mut None, None, left)?;
+            return self.next_geq_range(reader, value, right);
+        }
+        if left.is_empty() || left.high < value {
+            return Ok(Some(middle_value));
+        }
+        Ok(Some(self.next_geq_range(reader, value, left)?.unwrap_or(middle_value)))
+    }
+
+    /// Return an iterator over the values, which are decoded lazily.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        InterpolativeIterator::new(self)
+    }
+}
+
+/// A value to return or a subsequence still to decode.
+enum Frame {
+    Value(u64),
+    Subsequence(Subsequence),
+}
+
+/// Iterator over the values of an InterpolativeSequence.
+///
+/// The values are encoded in pre-order, the middle one and then the two
+/// halves, so they are returned in order by visiting the subsequences
+/// with a stack, which holds at most a logarithmic number of frames.
+struct InterpolativeIterator<'a> {
+    reader: BitReader<'a>,
+    stack: Vec<Frame>,
+    strict: bool,
+    remaining: usize,
+}
+
+impl<'a> InterpolativeIterator<'a> {
+    fn new(sequence: &'a InterpolativeSequence) -> InterpolativeIterator<'a> {
+        let mut stack = Vec::new();
+        if sequence.number_of_elements > 1 {
+            stack.push(Frame::Value(sequence.last_value));
+        }
+        if sequence.number_of_elements > 2 {
+            stack.push(Frame::Subsequence(sequence.inner()));
+        }
+        if sequence.number_of_elements > 0 {
+            stack.push(Frame::Value(sequence.first_value));
+        }
+        InterpolativeIterator{
+            reader: BitReader::new(&sequence.data, sequence.number_of_bits),
+            stack,
+            strict: sequence.strict,
+            remaining: sequence.number_of_elements,
+        }
+    }
+}
+
+impl<'a> Iterator for InterpolativeIterator<'a> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        loop {
+            match self.stack.pop()? {
+                Frame::Value(value) => {
+                    self.remaining -= 1;
+                    return Some(value);
+                }
+                Frame::Subsequence(range) => {
+                    let (lower, upper) = range.middle_bounds(self.strict);
+                    // the data was written by `from_vec` so it cannot be corrupted
+                    let value = lower + self.reader.read_minimal_binary((upper - lower) as u128 + 1).unwrap() as u64;
+                    let (left, right) = range.split(self.strict, value);
+                    if !right.is_empty() {
+                        self.stack.push(Frame::Subsequence(right));
+                    }
+                    self.stack.push(Frame::Value(value));
+                    if !left.is_empty() {
+                        self.stack.push(Frame::Subsequence(left));
+                    }
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl SortedSequence for InterpolativeSequence {
+    type Value = u64;
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.number_of_elements
+    }
+
+    fn select(&self, index: u64) -> Result<u64, String> {
+        let index = index as usize;
+        if index >= self.number_of_elements {
+            return Err(format!(
+                "Given index {} is out of bound on a collection with {} elements.",
+                index, self.number_of_elements
+            ));
+        }
+        if index == 0 {
+            return Ok(self.first_value);
+        }
+        if index == self.number_of_elements - 1 {
+            return Ok(self.last_value);
+        }
+        let mut reader = BitReader::new(&self.data, self.number_of_bits);
+        self.decode_range(&mut reader, &mut None, Some(index), self.inner())?
+            .ok_or_else(|| format!("The value of index {} was not decoded.", index))
+    }
+
+    fn decode(&self) -> Vec<u64> {
+        let mut result = vec![self.first_value; self.number_of_elements];
+        if let Some(last) = result.last_mut() {
+            *last = self.last_value;
+        }
+        if self.number_of_elements > 2 {
+            let mut reader = BitReader::new(&self.data, self.number_of_bits);
+            // the data was written by `from_vec` so it cannot be corrupted
+            self.decode_range(&mut reader, &mut Some(result.as_mut_slice()), None, self.inner()).unwrap();
+        }
+        result
+    }
+
+    fn next_geq(&self, value: u64) -> Option<u64> {
+        if self.is_empty() || value > self.last_value {
+            return None;
+        }
+        if value <= self.first_value {
+            return Some(self.first_value);
+        }
+        if self.number_of_elements <= 2 {
+            return Some(self.last_value);
+        }
+        let mut reader = BitReader::new(&self.data, self.number_of_bits);
+        // the data was written by `from_vec` so it cannot be corrupted
+        Some(self.next_geq_range(&mut reader, value, self.inner()).unwrap().unwrap_or(self.last_value))
+    }
+
+    #[inline]
+    fn size(&self) -> usize {
+        4 * core::mem::size_of::<u64>()
+            + core::mem::size_of::<usize>()
+            + core::mem::size_of::<bool>()
+            + self.data.capacity() * core::mem::size_of::<u64>()
+            + core::mem::size_of::<Vec<u64>>()
+    }
+}
@@ -1,40 +1,51 @@
 use super::*;
-use std::ops::Range;
+use core::ops::Range;
 
-impl EliasFano {
+impl<T: UnsignedInteger> EliasFano<T> {
     /// Return iterator for the values in elias fano using the old way with selects.
     /// This method is only meant for banchmarking.
     #[inline]
-    pub fn iter_select(&self) -> impl Iterator<Item = u64> + '_ {
+    pub fn iter_select(&self) -> impl Iterator<Item = T> + '_ {
         (0..self.current_number_of_elements).map(move |index| self.unchecked_select(index))
     }
 
     /// Return iterator for the values in elias fano.
     #[inline]
-    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
         self.high_bits.iter().enumerate().map(move |(index, high_bit_index)|{
             let high_value = high_bit_index - index as u64;
             let low_bits = self.read_lowbits(index as u64);
-            (high_value << self.low_bit_count) | low_bits
+            self.build_value(index as u64, high_value, low_bits)
         })
     }
 
     #[inline]
-    pub fn iter_in_range(&self, range: Range<u64>) -> impl Iterator<Item=u64> + '_ {
+    pub fn iter_in_range(&self, range: Range<T>) -> impl Iterator<Item=T> + '_ {
         let Range{
             start,
             end
         } = range;
         
         let offset = self.unchecked_rank(start);
-        let high_start = offset.saturating_add(start >> self.low_bit_count);
+        let high_start = if self.strict {
+            // the encoded values depend on the index, so start from the
+            // position of the first element in the range
+            match offset < self.current_number_of_elements {
+                true => self.high_bits.unchecked_select1(offset),
+                false => u64::MAX,
+            }
+        } else {
+            offset.saturating_add(
+                self.extract_high_bits(start.max(self.min_value) - self.min_value)
+            )
+        };
 
         self.high_bits.iter_in_range(high_start..u64::MAX).enumerate()
             .map(move |(index, high_bit_index)| {
                 let index = index as u64 + offset;
                 let high_value = high_bit_index - index;
                 let low_bits = self.read_lowbits(index);
-                let result = (high_value << self.low_bit_count) | low_bits;
+                let result = self.build_value(index, high_value, low_bits);
                 if result >= end {
                     None
                 } else {
@@ -45,8 +56,8 @@ impl EliasFano {
 
     /// Return iterator for the values in elias fano.
     #[inline]
-    pub fn iter_uniques(&self) -> impl Iterator<Item = u64> + '_ {
-        let mut last_value = 0;
+    pub fn iter_uniques(&self) -> impl Iterator<Item = T> + '_ {
+        let mut last_value = T::ZERO;
         let mut first = true;
         self.iter().filter_map(move |value| {
             match first || last_value != value {
@@ -62,7 +73,7 @@ impl EliasFano {
 
     /// Return iterator for the values in elias fano.
     #[inline]
-    pub fn enumerate(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+    pub fn enumerate(&self) -> impl Iterator<Item = (u64, T)> + '_ {
         (0..self.current_number_of_elements).map(move |index| (index, self.unchecked_select(index)))
     }
 }
\ No newline at end of file
@@ -54,7 +54,21 @@
 //!
 //! **TLDR**: [Vigna uses 256 (8)](https://shonan.nii.ac.jp/archives/seminar/029/wp-content/uploads/sites/12/2013/07/Sebastiano_Shonan.pdf) but 
 //! in our implementatione we use 1024 (10) as the default quantum (`INDEX_SHIFT`) because it provide a better time-memory tradeoff.
-#![feature(core_intrinsics)]
+#![cfg_attr(feature = "nightly", feature(core_intrinsics))]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+#[allow(unused_imports)]
+pub(crate) use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+mod hints;
+pub(crate) use hints::*;
 
 mod low_bits_primitives;
 #[cfg(feature="fuzz")]
@@ -68,8 +82,17 @@ pub use constants::*;
 #[cfg(not(feature="fuzz"))]
 pub(crate) use constants::*;
 
+mod integer;
+pub use integer::*;
+
+// the bitvector traits are not re-exported, so that a glob import of the
+// crate does not clash with the ones of the other dictionaries, like fid
+pub mod traits;
+pub use traits::SortedSequence;
+
 mod elias_fano;
 mod builders;
+pub(crate) use builders::compute_low_bit_count;
 mod utils_methods;
 pub use elias_fano::*;
 
@@ -78,13 +101,68 @@ pub use simple_select::*;
 
 mod hash;
 mod getters;
+mod batch;
+mod nearest;
 
 mod iter;
+#[cfg(feature = "std")]
 mod par_iter;
 
+#[cfg(feature = "std")]
 mod concurrent_builder;
+#[cfg(feature = "std")]
 pub use concurrent_builder::*;
 
+#[cfg(feature = "std")]
+mod external_builder;
+#[cfg(feature = "std")]
+pub use external_builder::*;
+
+#[cfg(feature = "std")]
+mod serialize;
+#[cfg(feature = "std")]
+pub use serialize::{CHECKSUM_SERIALIZATION_MAGIC, SERIALIZATION_MAGIC};
+#[cfg(feature = "std")]
+pub(crate) use serialize::*;
+
+mod prefix_sum;
+pub use prefix_sum::*;
+
+mod pair;
+pub use pair::*;
+
+#[cfg(feature = "std")]
+mod graph;
+#[cfg(feature = "std")]
+pub use graph::*;
+
+mod map;
+pub use map::*;
+
+mod compact_vec;
+pub use compact_vec::*;
+
+pub mod codes;
+pub use codes::{BitReader, BitWriter};
+
+mod interpolative;
+pub use interpolative::*;
+
+mod hybrid;
+pub use hybrid::*;
+
+mod chunked;
+pub use chunked::*;
+
+#[cfg(feature = "std")]
+mod roaring;
+
+mod validate;
+pub use validate::*;
+
+#[cfg(feature = "rand")]
+mod sample;
+
 #[cfg(feature="fuzz")]
 mod fuzz_harnesses;
 #[cfg(feature="fuzz")]
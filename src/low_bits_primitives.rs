@@ -1,5 +1,5 @@
 use super::*;
-use std::sync::atomic::{AtomicU64, Ordering};
+use core::sync::atomic::{AtomicU64, Ordering};
 
 /// Return the size needed to allcoate the choosen number of bits
 /// This is a bit bigger than the minimum amount because doing so allows
@@ -21,11 +21,43 @@ pub fn shr(value: u64, offset: u64) -> u64 {
     value.checked_shr(offset as u32).unwrap_or(0)
 }
 
-pub fn safe_write(array: &mut Vec<u64>, index: u64, value: u64, value_size: u64) {
-    let pos = index * value_size;
+// The helpers which address the values by index are only used by the tests,
+// the benchmarks and the fuzzing, the structures use the ones by bit position.
+#[cfg(any(test, feature = "fuzz"))]
+pub fn safe_write(array: &mut [u64], index: u64, value: u64, value_size: u64) {
+    safe_write_bits(array, index * value_size, value, value_size)
+}
+
+#[cfg(feature = "fuzz")]
+pub fn concurrent_write(array: &[AtomicU64], index: u64, value: u64, value_size: u64) {
+    concurrent_write_bits(array, index * value_size, value, value_size)
+}
+
+#[cfg(any(test, feature = "fuzz"))]
+#[inline(always)]
+pub fn safe_read(array: &[u64], index: u64, value_size: u64) -> u64 {
+    safe_read_bits(array, index * value_size, value_size)
+}
+
+#[cfg(any(test, feature = "fuzz"))]
+#[inline(always)]
+pub fn unsafe_write(array: &mut [u64], index: u64, value: u64, value_size: u64) {
+    unsafe_write_bits(array, index * value_size, value, value_size)
+}
+
+#[cfg(any(test, feature = "fuzz"))]
+#[inline(always)]
+pub fn unsafe_read(array: &[u64], index: u64, value_size: u64) -> u64 {
+    unsafe_read_bits(array, index * value_size, value_size)
+}
+
+/// Write the `value_size` lower bits of `value` starting at the bit `pos`.
+#[inline(always)]
+pub fn safe_write_bits(array: &mut [u64], pos: u64, value: u64, value_size: u64) {
     let o1 = pos & WORD_MASK;
     let o2 = WORD_SIZE - o1;
 
+    let value = value & shr(u64::MAX, WORD_SIZE - value_size);
     let lower = shl(value, o1);
     let higher = shr(value, o2);
 
@@ -34,11 +66,13 @@ pub fn safe_write(array: &mut Vec<u64>, index: u64, value: u64, value_size: u64)
     array[base + 1] |= higher;
 }
 
-pub fn concurrent_write(array: &Vec<AtomicU64>, index: u64, value: u64, value_size: u64) {
-    let pos = index * value_size;
+/// Concurrently write the `value_size` lower bits of `value` starting at the bit `pos`.
+#[inline(always)]
+pub fn concurrent_write_bits(array: &[AtomicU64], pos: u64, value: u64, value_size: u64) {
     let o1 = pos & WORD_MASK;
     let o2 = WORD_SIZE - o1;
 
+    let value = value & shr(u64::MAX, WORD_SIZE - value_size);
     let lower = shl(value, o1);
     let higher = shr(value, o2);
 
@@ -47,13 +81,51 @@ pub fn concurrent_write(array: &Vec<AtomicU64>, index: u64, value: u64, value_si
     array[base + 1].fetch_or(higher, Ordering::SeqCst);
 }
 
+/// Set to zero the `value_size` bits starting at the bit `pos`.
 #[inline(always)]
-pub fn safe_read(array: &[u64], index: u64, value_size: u64) -> u64 {
-    let pos = index * value_size;
+pub fn safe_clear_bits(array: &mut [u64], pos: u64, value_size: u64) {
+    let o1 = pos & WORD_MASK;
+    let o2 = WORD_SIZE - o1;
+
+    let mask = shr(u64::MAX, WORD_SIZE - value_size);
+    let base = (pos >> WORD_SHIFT) as usize;
+    array[base] &= !shl(mask, o1);
+    array[base + 1] &= !shr(mask, o2);
+}
+
+/// Concurrently set to zero the `value_size` bits starting at the bit `pos`.
+#[inline(always)]
+pub fn concurrent_clear_bits(array: &[AtomicU64], pos: u64, value_size: u64) {
+    let o1 = pos & WORD_MASK;
+    let o2 = WORD_SIZE - o1;
+
+    let mask = shr(u64::MAX, WORD_SIZE - value_size);
+    let base = (pos >> WORD_SHIFT) as usize;
+    array[base].fetch_and(!shl(mask, o1), Ordering::SeqCst);
+    array[base + 1].fetch_and(!shr(mask, o2), Ordering::SeqCst);
+}
+
+/// Concurrently read `value_size` bits starting at the bit `pos`.
+#[inline(always)]
+pub fn concurrent_read_bits(array: &[AtomicU64], pos: u64, value_size: u64) -> u64 {
+    let o1 = pos & WORD_MASK;
+    let o2 = WORD_SIZE - o1;
+
+    let mask = shr(u64::MAX, WORD_SIZE - value_size);
+    let base = (pos >> WORD_SHIFT) as usize;
+    let lower = shr(array[base].load(Ordering::SeqCst), o1) & mask;
+    let higher = shl(array[base + 1].load(Ordering::SeqCst), o2);
+
+    (higher | lower) & mask
+}
+
+/// Read `value_size` bits starting at the bit `pos`.
+#[inline(always)]
+pub fn safe_read_bits(array: &[u64], pos: u64, value_size: u64) -> u64 {
     let o1 = pos & WORD_MASK;
     let o2 = WORD_SIZE - o1;
 
-    let mask = (1 << value_size) - 1;
+    let mask = shr(u64::MAX, WORD_SIZE - value_size);
     let base = (pos >> WORD_SHIFT) as usize;
     let lower = shr(array[base], o1) & mask;
     let higher = shl(array[base + 1], o2);
@@ -61,13 +133,16 @@ pub fn safe_read(array: &[u64], index: u64, value_size: u64) -> u64 {
     (higher | lower) & mask
 }
 
+/// Write the `value_size` lower bits of `value` starting at the bit `pos`
+/// without bounds checks.
+#[cfg(any(test, feature = "fuzz", feature = "unsafe"))]
 #[inline(always)]
-pub fn unsafe_write(array: &mut Vec<u64>, index: u64, value: u64, value_size: u64) {
-    let pos = index * value_size;
+pub fn unsafe_write_bits(array: &mut [u64], pos: u64, value: u64, value_size: u64) {
     let o1 = pos & WORD_MASK;
     let o2 = WORD_SIZE - o1;
 
     let base = (pos >> WORD_SHIFT) as usize;
+    let value = value & shr(u64::MAX, WORD_SIZE - value_size);
     let lower = shl(value, o1);
     let higher = shr(value, o2);
 
@@ -77,13 +152,14 @@ pub fn unsafe_write(array: &mut Vec<u64>, index: u64, value: u64, value_size: u6
     }
 }
 
+/// Read `value_size` bits starting at the bit `pos` without bounds checks.
+#[cfg(any(test, feature = "fuzz", feature = "unsafe"))]
 #[inline(always)]
-pub fn unsafe_read(array: &[u64], index: u64, value_size: u64) -> u64 {
-    let pos = index * value_size;
+pub fn unsafe_read_bits(array: &[u64], pos: u64, value_size: u64) -> u64 {
     let o1 = pos & WORD_MASK;
     let o2 = WORD_SIZE - o1;
 
-    let mask = (1 << value_size) - 1;
+    let mask = shr(u64::MAX, WORD_SIZE - value_size);
     let base = (pos >> WORD_SHIFT) as usize;
     unsafe {
         let lower = shr(*array.get_unchecked(base), o1) & mask;
@@ -92,6 +168,26 @@ pub fn unsafe_read(array: &[u64], index: u64, value_size: u64) -> u64 {
     }
 }
 
+/// Read `value_size` bits starting at the bit `pos`, with or without bounds
+/// checks depending on the `unsafe` feature.
+#[inline(always)]
+pub(crate) fn read_bits(array: &[u64], pos: u64, value_size: u64) -> u64 {
+    #[cfg(not(feature = "unsafe"))]
+    return safe_read_bits(array, pos, value_size);
+    #[cfg(feature = "unsafe")]
+    return unsafe_read_bits(array, pos, value_size);
+}
+
+/// Write the `value_size` lower bits of `value` starting at the bit `pos`, with or without bounds
+/// checks depending on the `unsafe` feature.
+#[inline(always)]
+pub(crate) fn write_bits(array: &mut [u64], pos: u64, value: u64, value_size: u64) {
+    #[cfg(not(feature = "unsafe"))]
+    safe_write_bits(array, pos, value, value_size);
+    #[cfg(feature = "unsafe")]
+    unsafe_write_bits(array, pos, value, value_size);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,149 @@
+use super::*;
+use core::ops::Range;
+
+#[derive(Clone, Debug, PartialEq)]
+/// Static map from sorted unique u64 keys to values.
+/// The keys are stored in an EliasFano and the values in a vector
+/// in the same order, so the value of a key is at the index given by its rank.
+///
+/// The keys are unique but they are not stored in a strict EliasFano,
+/// because there the rank is a binary search over the selects, while the
+/// non-strict rank reaches the bucket of the key with a `select0` and scans
+/// it, so `get` takes constant time on average instead of O(log n).
+///
+/// ```
+/// # use elias_fano_rust::EliasFanoMap;
+/// let map = EliasFanoMap::from_vec(&[3, 10, 100, 1000], vec!["a", "b", "c", "d"]).unwrap();
+/// assert_eq!(map.get(100), Some(&"c"));
+/// assert_eq!(map.get(99), None);
+/// assert_eq!(map.range(5..500).collect::<Vec<_>>(), vec![(10, &"b"), (100, &"c")]);
+/// ```
+pub struct EliasFanoMap<V> {
+    keys: EliasFano<u64>,
+    values: Vec<V>,
+}
+
+impl<V> EliasFanoMap<V> {
+    /// Create a map from an iterator of `(key, value)` with **strictly increasing** keys.
+    ///
+    /// # Arguments
+    ///
+    /// * items: impl Iterator<Item = (u64, V)> - Iterator of the items sorted by key.
+    /// * min_key: u64 - The minimum key.
+    /// * max_key: u64 - The maximum key.
+    /// * number_of_elements: usize - The number of items in the iterator.
+    pub fn from_iter(
+        items: impl Iterator<Item = (u64, V)>,
+        min_key: u64,
+        max_key: u64,
+        number_of_elements: usize,
+    ) -> Result<EliasFanoMap<V>, String> {
+        let mut keys = EliasFano::new_with_min_value(min_key, max_key, number_of_elements)?;
+        let mut values = Vec::with_capacity(number_of_elements);
+        for (key, value) in items {
+            if !keys.is_empty() && keys.last_value == key {
+                return Err(format!("The key {} is duplicated.", key));
+            }
+            keys.push(key)?;
+            values.push(value);
+        }
+        if values.len() != number_of_elements {
+            return Err(format!(
+                "The iterator yielded {} items but {} were expected.",
+                values.len(), number_of_elements
+            ));
+        }
+        Ok(EliasFanoMap{keys, values})
+    }
+
+    /// Create a map from a slice of **strictly increasing** keys and
+    /// the vector of their values.
+    ///
+    /// # Arguments
+    ///
+    /// * keys: &[u64] - The sorted keys.
+    /// * values: Vec<V> - The values, in the same order of the keys.
+    pub fn from_vec(keys: &[u64], values: Vec<V>) -> Result<EliasFanoMap<V>, String> {
+        if keys.len() != values.len() {
+            return Err(format!(
+                "The map has {} keys but {} values.",
+                keys.len(), values.len()
+            ));
+        }
+        if let Some(window) = keys.windows(2).find(|window| window[0] == window[1]) {
+            return Err(format!("The key {} is duplicated.", window[0]));
+        }
+        Ok(EliasFanoMap{
+            keys: EliasFano::from_vec_auto_min(keys)?,
+            values,
+        })
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Return the EliasFano with the keys.
+    #[inline]
+    pub fn keys(&self) -> &EliasFano<u64> {
+        &self.keys
+    }
+
+    /// Return the values sorted by key.
+    #[inline]
+    pub fn values(&self) -> &[V] {
+        &self.values
+    }
+
+    #[inline]
+    pub fn contains_key(&self, key: u64) -> bool {
+        self.keys.contains(key)
+    }
+
+    /// Return the value associated with the given key, if present.
+    /// This takes constant time on average, like the rank of the keys.
+    #[inline]
+    pub fn get(&self, key: u64) -> Option<&V> {
+        self.keys.rank(key).map(|index| &self.values[index as usize])
+    }
+
+    /// Return a mutable reference to the value associated with the given key, if present.
+    #[inline]
+    pub fn get_mut(&mut self, key: u64) -> Option<&mut V> {
+        let index = self.keys.rank(key)?;
+        Some(&mut self.values[index as usize])
+    }
+
+    /// Return the key and the value of the chosen index.
+    #[inline]
+    pub fn select(&self, index: u64) -> Result<(u64, &V), String> {
+        Ok((self.keys.select(index)?, &self.values[index as usize]))
+    }
+
+    /// Return an iterator over the items whose key is in the given range, sorted by key.
+    #[inline]
+    pub fn range(&self, range: Range<u64>) -> impl Iterator<Item = (u64, &V)> + '_ {
+        let offset = self.keys.unchecked_rank(range.start) as usize;
+        self.keys.iter_in_range(range).zip(self.values[offset..].iter())
+    }
+
+    /// Return an iterator over the items sorted by key.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &V)> + '_ {
+        self.keys.iter().zip(self.values.iter())
+    }
+
+    /// Return the memory used in bytes, not counting the memory owned by the values.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.keys.size()
+            + self.values.capacity() * core::mem::size_of::<V>()
+            + core::mem::size_of::<Vec<V>>()
+    }
+}
@@ -0,0 +1,75 @@
+use super::*;
+
+impl<T: UnsignedInteger> EliasFano<T> {
+    /// Return the index and the value of the element closest to the given
+    /// value, or None if the set is empty. On ties the smaller element wins.
+    ///
+    /// ```rust
+    /// # use elias_fano_rust::EliasFano;
+    /// let ef = EliasFano::from_vec(&[5_u64, 8, 8, 15, 32]).unwrap();
+    /// assert_eq!(ef.nearest(12), Some((3, 15)));
+    /// assert_eq!(ef.nearest(10), Some((2, 8)));
+    /// assert_eq!(ef.nearest(100), Some((4, 32)));
+    /// ```
+    #[inline]
+    pub fn nearest(&self, value: T) -> Option<(u64, T)> {
+        self.k_nearest(value, 1).pop()
+    }
+
+    /// Return the indices and the values of the `k` elements closest to the
+    /// given value, sorted by distance and, on ties, smaller elements first.
+    /// If the set has less than `k` elements, all of them are returned.
+    ///
+    /// The elements around the value are found with a rank and then the
+    /// high-bits are decoded in both directions with two double-ended
+    /// iterators, taking each time the closest of the two candidates.
+    ///
+    /// ```rust
+    /// # use elias_fano_rust::EliasFano;
+    /// let ef = EliasFano::from_vec(&[5_u64, 8, 8, 15, 32]).unwrap();
+    /// assert_eq!(ef.k_nearest(12, 3), vec![(3, 15), (2, 8), (1, 8)]);
+    /// assert_eq!(ef.k_nearest(0, 10).len(), 5);
+    /// ```
+    pub fn k_nearest(&self, value: T, k: usize) -> Vec<(u64, T)> {
+        let mut result = Vec::with_capacity(k.min(self.len()));
+        if k == 0 || self.is_empty() {
+            return result;
+        }
+
+        // the position in the high-bits of the first element >= value
+        let rank = self.unchecked_rank(value);
+        let position = match rank < self.current_number_of_elements {
+            true => self.high_bits.unchecked_select1(rank),
+            false => self.high_bits.len(),
+        };
+        let decode = move |(high_bit_index, index): (u64, u64)| {
+            (index, self.build_value(index, high_bit_index - index, self.read_lowbits(index)))
+        };
+        let mut smaller = self.high_bits.iter_in_range_double_ended(0..position).rev()
+            .zip((0..rank).rev())
+            .map(decode);
+        let mut greater = self.high_bits.iter_in_range_double_ended(position..self.high_bits.len())
+            .zip(rank..self.current_number_of_elements)
+            .map(decode);
+
+        let (mut left, mut right) = (smaller.next(), greater.next());
+        while result.len() < k {
+            match (left, right) {
+                (Some(l), Some(r)) if value - l.1 <= r.1 - value => {
+                    result.push(l);
+                    left = smaller.next();
+                },
+                (_, Some(r)) => {
+                    result.push(r);
+                    right = greater.next();
+                },
+                (Some(l), None) => {
+                    result.push(l);
+                    left = smaller.next();
+                },
+                (None, None) => break,
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,292 @@
+use super::*;
+#[cfg(feature = "std")]
+use rayon::iter::IndexedParallelIterator;
+#[cfg(feature = "std")]
+use rayon::prelude::*;
+use core::fmt::Debug;
+use core::ops::Range;
+
+/// Bijection between pairs of integers and u64 which preserves the
+/// lexicographic order, so all the pairs with the same first component
+/// are a contiguous range of packed values.
+///
+/// The packed values are always smaller than `u64::MAX` so that the range
+/// of the pairs with any first component can be expressed as a `Range<u64>`.
+pub trait Packer: Clone + Debug + PartialEq + Send + Sync {
+    /// Return if the pair can be packed.
+    fn is_valid(&self, first: u64, second: u64) -> bool;
+
+    /// Pack the pair without checking if it's valid.
+    fn pack(&self, first: u64, second: u64) -> u64;
+
+    /// Return the pair encoded in the given value.
+    fn unpack(&self, value: u64) -> (u64, u64);
+
+    /// Return the biggest value that can be returned by `pack`.
+    fn universe(&self) -> u64;
+
+    /// Return the range of the packed values of the pairs whose first
+    /// component is `first`, which must be a valid first component.
+    #[inline]
+    fn first_range(&self, first: u64) -> Range<u64> {
+        self.pack(first, 0)..self.pack(first + 1, 0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Pack the pairs as `first << second_bits | second`.
+pub struct ShiftPacker {
+    first_bits: u64,
+    second_bits: u64,
+}
+
+impl ShiftPacker {
+    /// Create a packer for pairs whose components fit in the given bits.
+    /// The total number of bits must be at most 63.
+    pub fn new(first_bits: u64, second_bits: u64) -> Result<ShiftPacker, String> {
+        if first_bits + second_bits > 63 {
+            return Err(format!(
+                concat!(
+                    "Cannot pack {} bits for the first component and {} bits ",
+                    "for the second one, at most 63 bits are supported."
+                ),
+                first_bits, second_bits
+            ));
+        }
+        Ok(ShiftPacker{first_bits, second_bits})
+    }
+}
+
+impl Packer for ShiftPacker {
+    #[inline]
+    fn is_valid(&self, first: u64, second: u64) -> bool {
+        (first >> self.first_bits) == 0 && (second >> self.second_bits) == 0
+    }
+
+    #[inline]
+    fn pack(&self, first: u64, second: u64) -> u64 {
+        (first << self.second_bits) | second
+    }
+
+    #[inline]
+    fn unpack(&self, value: u64) -> (u64, u64) {
+        (value >> self.second_bits, value & ((1 << self.second_bits) - 1))
+    }
+
+    #[inline]
+    fn universe(&self) -> u64 {
+        (1 << (self.first_bits + self.second_bits)) - 1
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Pack the pairs as `first * number_of_seconds + second`, this is more
+/// compact than `ShiftPacker` when the bounds are not powers of two.
+pub struct MulPacker {
+    number_of_firsts: u64,
+    number_of_seconds: u64,
+}
+
+impl MulPacker {
+    /// Create a packer for pairs in `[0, number_of_firsts) x [0, number_of_seconds)`.
+    pub fn new(number_of_firsts: u64, number_of_seconds: u64) -> Result<MulPacker, String> {
+        if number_of_firsts.checked_mul(number_of_seconds).is_none() {
+            return Err(format!(
+                concat!(
+                    "Cannot pack pairs in [0, {}) x [0, {}) ",
+                    "because the packed values do not fit in a u64."
+                ),
+                number_of_firsts, number_of_seconds
+            ));
+        }
+        Ok(MulPacker{number_of_firsts, number_of_seconds})
+    }
+
+    #[inline]
+    pub fn get_number_of_firsts(&self) -> u64 {
+        self.number_of_firsts
+    }
+
+    #[inline]
+    pub fn get_number_of_seconds(&self) -> u64 {
+        self.number_of_seconds
+    }
+}
+
+impl Packer for MulPacker {
+    #[inline]
+    fn is_valid(&self, first: u64, second: u64) -> bool {
+        first < self.number_of_firsts && second < self.number_of_seconds
+    }
+
+    #[inline]
+    fn pack(&self, first: u64, second: u64) -> u64 {
+        first * self.number_of_seconds + second
+    }
+
+    #[inline]
+    fn unpack(&self, value: u64) -> (u64, u64) {
+        (value / self.number_of_seconds, value % self.number_of_seconds)
+    }
+
+    #[inline]
+    fn universe(&self) -> u64 {
+        (self.number_of_firsts * self.number_of_seconds).saturating_sub(1)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// EliasFano over pairs of integers packed with the given `Packer`.
+///
+/// ```
+/// # use elias_fano_rust::{PairEliasFano, ShiftPacker};
+/// let pairs = [(0, 7), (1, 2), (1, 3), (3, 0)];
+/// let ef = PairEliasFano::from_vec(&pairs, ShiftPacker::new(2, 3).unwrap()).unwrap();
+/// assert_eq!(ef.iter_with_first(1).collect::<Vec<_>>(), vec![(1, 2), (1, 3)]);
+/// assert_eq!(ef.count_with_first(2), 0);
+/// assert!(ef.contains(3, 0));
+/// ```
+pub struct PairEliasFano<P: Packer> {
+    packer: P,
+    values: EliasFano<u64>,
+}
+
+impl<P: Packer> PairEliasFano<P> {
+    /// Create an EliasFano from an iterator of pairs **sorted** lexicographically.
+    ///
+    /// # Arguments
+    ///
+    /// * pairs: impl Iterator<Item = (u64, u64)> - Iterator of the sorted pairs.
+    /// * packer: P - The packer used to encode the pairs.
+    /// * number_of_elements: usize - The number of pairs in the iterator.
+    pub fn from_iter(
+        pairs: impl Iterator<Item = (u64, u64)>,
+        packer: P,
+        number_of_elements: usize,
+    ) -> Result<PairEliasFano<P>, String> {
+        let mut values = EliasFano::new(packer.universe(), number_of_elements)?;
+        for (first, second) in pairs {
+            if !packer.is_valid(first, second) {
+                return Err(format!(
+                    "The pair ({}, {}) cannot be packed by the packer {:?}.",
+                    first, second, packer
+                ));
+            }
+            values.push(packer.pack(first, second))?;
+        }
+        Ok(PairEliasFano{packer, values})
+    }
+
+    /// Create an EliasFano from a slice of pairs **sorted** lexicographically.
+    ///
+    /// # Arguments
+    ///
+    /// * pairs: &[(u64, u64)] - The sorted pairs.
+    /// * packer: P - The packer used to encode the pairs.
+    pub fn from_vec(pairs: &[(u64, u64)], packer: P) -> Result<PairEliasFano<P>, String> {
+        PairEliasFano::from_iter(pairs.iter().cloned(), packer, pairs.len())
+    }
+
+    /// Create a PairEliasFano from an EliasFano of already packed values.
+    pub fn from_elias_fano(values: EliasFano<u64>, packer: P) -> Result<PairEliasFano<P>, String> {
+        if values.get_universe() > packer.universe() {
+            return Err(format!(
+                "The universe {} is bigger than the one of the packer {:?}.",
+                values.get_universe(), packer
+            ));
+        }
+        Ok(PairEliasFano{packer, values})
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    #[inline]
+    pub fn get_packer(&self) -> &P {
+        &self.packer
+    }
+
+    /// Return the underlying EliasFano with the packed pairs.
+    #[inline]
+    pub fn as_elias_fano(&self) -> &EliasFano<u64> {
+        &self.values
+    }
+
+    /// Return the pair of the chosen index.
+    #[inline]
+    pub fn select(&self, index: u64) -> Result<(u64, u64), String> {
+        Ok(self.packer.unpack(self.values.select(index)?))
+    }
+
+    /// Return the index of the first instance of the given pair, if present.
+    #[inline]
+    pub fn rank(&self, first: u64, second: u64) -> Option<u64> {
+        if !self.packer.is_valid(first, second) {
+            return None;
+        }
+        self.values.rank(self.packer.pack(first, second))
+    }
+
+    #[inline]
+    pub fn contains(&self, first: u64, second: u64) -> bool {
+        self.packer.is_valid(first, second)
+            && self.values.contains(self.packer.pack(first, second))
+    }
+
+    /// Return an iterator over the pairs whose first component is `first`.
+    #[inline]
+    pub fn iter_with_first(&self, first: u64) -> impl Iterator<Item = (u64, u64)> + '_ {
+        let range = match self.packer.is_valid(first, 0) {
+            true => self.packer.first_range(first),
+            false => 0..0,
+        };
+        self.values.iter_in_range(range)
+            .map(move |value| self.packer.unpack(value))
+    }
+
+    /// Return the number of pairs whose first component is `first`.
+    #[inline]
+    pub fn count_with_first(&self, first: u64) -> u64 {
+        if !self.packer.is_valid(first, 0) {
+            return 0;
+        }
+        let Range{start, end} = self.packer.first_range(first);
+        self.values.unchecked_rank(end) - self.values.unchecked_rank(start)
+    }
+
+    /// Return an iterator over the pairs in the given lexicographic range.
+    /// Both the bounds must be valid pairs.
+    #[inline]
+    pub fn iter_in_range(&self, range: Range<(u64, u64)>) -> impl Iterator<Item = (u64, u64)> + '_ {
+        let start = self.packer.pack(range.start.0, range.start.1);
+        let end = self.packer.pack(range.end.0, range.end.1);
+        self.values.iter_in_range(start..end)
+            .map(move |value| self.packer.unpack(value))
+    }
+
+    /// Return an iterator over the pairs.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        self.values.iter().map(move |value| self.packer.unpack(value))
+    }
+
+    /// Return a parallel iterator over the pairs.
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn par_iter(&self) -> impl IndexedParallelIterator<Item = (u64, u64)> + '_ {
+        self.values.par_iter().map(move |value| self.packer.unpack(value))
+    }
+
+    /// Return the memory used in bytes
+    #[inline]
+    pub fn size(&self) -> usize {
+        core::mem::size_of::<P>() + self.values.size()
+    }
+}
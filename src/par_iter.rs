@@ -3,10 +3,10 @@ use rayon::iter::{ParallelIterator, IndexedParallelIterator};
 use rayon::prelude::*;
 use std::ops::Range;
 
-impl EliasFano {
+impl<T: UnsignedInteger> EliasFano<T> {
     /// Return iterator for the values in elias fano.
     #[inline]
-    pub fn par_iter(&self) -> impl IndexedParallelIterator<Item = u64> + '_ {
+    pub fn par_iter(&self) -> impl IndexedParallelIterator<Item = T> + '_ {
         (0..self.current_number_of_elements as usize)
             .into_par_iter()
             .map(move |index| self.unchecked_select(index as u64))
@@ -14,7 +14,7 @@ impl EliasFano {
 
     /// Return a parallel iterator for the values in elias fano.
     #[inline]
-    pub fn par_iter_uniques(&self) -> impl ParallelIterator<Item = u64> + '_ {
+    pub fn par_iter_uniques(&self) -> impl ParallelIterator<Item = T> + '_ {
         (0..self.current_number_of_elements).into_par_iter()
             .filter_map(move |index| {
                 if index == 0 {
@@ -0,0 +1,219 @@
+use super::*;
+#[cfg(feature = "std")]
+use std::io::{Read, Write};
+
+#[derive(Clone, Debug, PartialEq)]
+/// Elias-Fano encoding of the prefix sums of a sequence of lengths,
+/// this is the classic offsets array of a CSR, where the bucket `i`
+/// spans the positions in `[offsets[i], offsets[i + 1])`.
+///
+/// The offsets are `n + 1` non-decreasing values, starting from 0
+/// and ending with the sum of all the lengths.
+///
+/// ```
+/// # use elias_fano_rust::EliasFanoPrefixSum;
+/// let offsets = EliasFanoPrefixSum::from_vec(&[3, 0, 2, 5]).unwrap();
+/// assert_eq!(offsets.len(), 4);
+/// assert_eq!(offsets.get(2).unwrap(), 3);
+/// assert_eq!(offsets.get_pair(2).unwrap(), (3, 5));
+/// assert_eq!(offsets.bucket_len(1).unwrap(), 0);
+/// assert_eq!(offsets.find(4), Some(2));
+/// assert_eq!(offsets.find(10), None);
+/// ```
+pub struct EliasFanoPrefixSum {
+    offsets: EliasFano<u64>,
+}
+
+impl EliasFanoPrefixSum {
+    /// Create the prefix sums from an iterator of lengths.
+    ///
+    /// # Arguments
+    ///
+    /// * lengths: impl Iterator<Item = u64> - The lengths of the buckets.
+    /// * total: u64 - The sum of all the lengths.
+    /// * number_of_buckets: usize - The number of lengths in the iterator.
+    pub fn from_iter(
+        lengths: impl Iterator<Item = u64>,
+        total: u64,
+        number_of_buckets: usize,
+    ) -> Result<EliasFanoPrefixSum, String> {
+        let mut offsets = EliasFano::new(total, number_of_buckets + 1)?;
+        let mut sum: u64 = 0;
+        offsets.push(0)?;
+        for length in lengths {
+            sum = match sum.checked_add(length) {
+                Some(sum) if sum <= total => sum,
+                _ => return Err(format!(
+                    concat!(
+                        "The sum of the lengths exceeds the given total {} ",
+                        "at bucket {}."
+                    ),
+                    total, offsets.len() - 1
+                )),
+            };
+            offsets.push(sum)?;
+        }
+
+        if offsets.len() != number_of_buckets + 1 {
+            return Err(format!(
+                "The iterator yielded {} lengths but {} were expected.",
+                offsets.len() - 1, number_of_buckets
+            ));
+        }
+
+        if sum != total {
+            return Err(format!(
+                "The sum of the lengths is {} but the given total is {}.",
+                sum, total
+            ));
+        }
+
+        Ok(EliasFanoPrefixSum{offsets})
+    }
+
+    /// Create the prefix sums from a slice of lengths.
+    ///
+    /// # Arguments
+    ///
+    /// * lengths: &[u64] - The lengths of the buckets.
+    pub fn from_vec(lengths: &[u64]) -> Result<EliasFanoPrefixSum, String> {
+        let total = lengths.iter().try_fold(0_u64, |sum, length| sum.checked_add(*length))
+            .ok_or_else(|| "The sum of the lengths does not fit in a u64.".to_string())?;
+        EliasFanoPrefixSum::from_iter(lengths.iter().cloned(), total, lengths.len())
+    }
+
+    /// Return the number of buckets.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the sum of all the lengths.
+    #[inline]
+    pub fn total(&self) -> u64 {
+        self.offsets.last_value
+    }
+
+    /// Return the underlying EliasFano with the `len() + 1` offsets.
+    #[inline]
+    pub fn as_elias_fano(&self) -> &EliasFano<u64> {
+        &self.offsets
+    }
+
+    /// Return the sum of the first `index` lengths, so `get(0)` is always 0
+    /// and `get(len())` is the total.
+    #[inline]
+    pub fn get(&self, index: u64) -> Result<u64, String> {
+        match index <= self.len() as u64 {
+            true => Ok(self.unchecked_get(index)),
+            false => Err(format!(
+                "Given index {} is out of bound on a prefix sum with {} offsets.",
+                index, self.len() + 1
+            )),
+        }
+    }
+
+    /// Return the sum of the first `index` lengths without executing checks.
+    #[inline]
+    pub fn unchecked_get(&self, index: u64) -> u64 {
+        self.offsets.unchecked_select(index)
+    }
+
+    /// Return the start and end offsets of the bucket `index`,
+    /// the end is found by scanning the high-bits from the start instead
+    /// of doing a second select.
+    #[inline]
+    pub fn get_pair(&self, index: u64) -> Result<(u64, u64), String> {
+        match index < self.len() as u64 {
+            true => Ok(self.unchecked_get_pair(index)),
+            false => Err(format!(
+                "Given index {} is out of bound on a prefix sum with {} buckets.",
+                index, self.len()
+            )),
+        }
+    }
+
+    /// Return the start and end offsets of the bucket `index` without executing checks.
+    #[inline]
+    pub fn unchecked_get_pair(&self, index: u64) -> (u64, u64) {
+        let start_position = self.offsets.high_bits.unchecked_select1(index);
+        let end_position = self.offsets.high_bits
+            .iter_in_range(start_position + 1..u64::MAX)
+            .next()
+            .unwrap();
+        (
+            self.offsets.build_value(
+                index,
+                start_position - index,
+                self.offsets.read_lowbits(index),
+            ),
+            self.offsets.build_value(
+                index + 1,
+                end_position - index - 1,
+                self.offsets.read_lowbits(index + 1),
+            ),
+        )
+    }
+
+    /// Return the length of the bucket `index`.
+    #[inline]
+    pub fn bucket_len(&self, index: u64) -> Result<u64, String> {
+        let (start, end) = self.get_pair(index)?;
+        Ok(end - start)
+    }
+
+    /// Return the index of the bucket which contains the given position,
+    /// so the only non-empty bucket `i` such that `get(i) <= position < get(i + 1)`.
+    /// If the position is not smaller than the total, None is returned.
+    #[inline]
+    pub fn find(&self, position: u64) -> Option<u64> {
+        if position >= self.total() {
+            return None;
+        }
+        // number of offsets <= position, minus one
+        Some(self.offsets.unchecked_rank(position + 1) - 1)
+    }
+
+    /// Return an iterator over the `len() + 1` offsets.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        self.offsets.iter()
+    }
+
+    /// Return an iterator over the lengths of the buckets.
+    #[inline]
+    pub fn iter_lengths(&self) -> impl Iterator<Item = u64> + '_ {
+        self.offsets.iter().zip(self.offsets.iter().skip(1))
+            .map(|(start, end)| end - start)
+    }
+
+    /// Return the memory used in bytes
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.offsets.size()
+    }
+
+    /// Write the prefix sums to the given writer, the format is the one
+    /// of the underlying EliasFano.
+    #[cfg(feature = "std")]
+    pub fn serialize(&self, writer: &mut impl Write) -> Result<(), String> {
+        self.offsets.serialize(writer)
+    }
+
+    /// Read prefix sums written by `serialize`.
+    #[cfg(feature = "std")]
+    pub fn deserialize(reader: &mut impl Read) -> Result<EliasFanoPrefixSum, String> {
+        let offsets = EliasFano::<u64>::deserialize(reader)?;
+        if offsets.is_empty() || offsets.unchecked_select(0) != 0 {
+            return Err(
+                "The serialized EliasFano is not a valid prefix sum, its first offset must be 0.".to_string()
+            );
+        }
+        Ok(EliasFanoPrefixSum{offsets})
+    }
+}
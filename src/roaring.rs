@@ -0,0 +1,249 @@
+use super::*;
+use std::io::{Read, Write};
+
+/// Cookie of the streams which may contain run containers.
+const SERIAL_COOKIE: u32 = 12347;
+/// Cookie of the streams without run containers.
+const SERIAL_COOKIE_NO_RUNCONTAINER: u32 = 12346;
+/// Streams with run containers and less containers than this have no offsets.
+const NO_OFFSET_THRESHOLD: usize = 4;
+/// Containers with more values than this are bitmaps.
+const MAX_ARRAY_CARDINALITY: usize = 4096;
+/// Number of words of a bitmap container.
+const BITMAP_WORDS: usize = 1024;
+
+#[inline]
+fn write_le(writer: &mut impl Write, bytes: &[u8]) -> Result<(), String> {
+    writer.write_all(bytes)
+        .map_err(|e| format!("Cannot write to the Roaring stream: {}", e))
+}
+
+#[inline]
+fn read_le<const N: usize>(reader: &mut impl Read) -> Result<[u8; N], String> {
+    let mut buffer = [0_u8; N];
+    reader.read_exact(&mut buffer)
+        .map_err(|e| format!("Cannot read from the Roaring stream: {}", e))?;
+    Ok(buffer)
+}
+
+#[inline]
+fn read_u16(reader: &mut impl Read) -> Result<u16, String> {
+    Ok(u16::from_le_bytes(read_le(reader)?))
+}
+
+#[inline]
+fn read_u32(reader: &mut impl Read) -> Result<u32, String> {
+    Ok(u32::from_le_bytes(read_le(reader)?))
+}
+
+/// Read a set of u32 in the portable Roaring serialization format
+/// and return its values sorted.
+///
+/// The format is described at <https://github.com/RoaringBitmap/RoaringFormatSpec>:
+/// a cookie, the keys and cardinalities of the containers, optionally their
+/// offsets and then the containers which can be arrays, bitmaps or runs.
+pub(crate) fn read_roaring(reader: &mut impl Read) -> Result<Vec<u64>, String> {
+    let cookie = read_u32(reader)?;
+    let (number_of_containers, run_flags) = if cookie & 0xffff == SERIAL_COOKIE {
+        let number_of_containers = (cookie >> 16) as usize + 1;
+        let mut run_flags = vec![0_u8; number_of_containers.div_ceil(8)];
+        reader.read_exact(&mut run_flags)
+            .map_err(|e| format!("Cannot read from the Roaring stream: {}", e))?;
+        (number_of_containers, run_flags)
+    } else if cookie == SERIAL_COOKIE_NO_RUNCONTAINER {
+        let number_of_containers = read_u32(reader)? as usize;
+        (number_of_containers, vec![0_u8; number_of_containers.div_ceil(8)])
+    } else {
+        return Err(format!(
+            "The stream is not in the Roaring format, it starts with the cookie {}.",
+            cookie
+        ));
+    };
+
+    if number_of_containers > 1 << 16 {
+        return Err(format!(
+            "The Roaring stream is corrupted, it has {} containers but at most 65536 are possible.",
+            number_of_containers
+        ));
+    }
+
+    let mut header = Vec::with_capacity(number_of_containers);
+    for _ in 0..number_of_containers {
+        let key = read_u16(reader)?;
+        let cardinality = read_u16(reader)? as usize + 1;
+        if let Some((last_key, _)) = header.last() {
+            if key <= *last_key {
+                return Err(format!(
+                    "The Roaring stream is corrupted, the key {} follows the key {}.",
+                    key, last_key
+                ));
+            }
+        }
+        header.push((key, cardinality));
+    }
+
+    // the containers are contiguous so the offsets are not needed
+    if cookie == SERIAL_COOKIE_NO_RUNCONTAINER || number_of_containers >= NO_OFFSET_THRESHOLD {
+        for _ in 0..number_of_containers {
+            read_u32(reader)?;
+        }
+    }
+
+    let mut result = Vec::new();
+    for (index, (key, cardinality)) in header.into_iter().enumerate() {
+        let high = (key as u64) << 16;
+        let start = result.len();
+        if run_flags[index >> 3] & (1 << (index & 7)) != 0 {
+            let number_of_runs = read_u16(reader)?;
+            for _ in 0..number_of_runs {
+                let run_start = read_u16(reader)? as u64;
+                let run_end = run_start + read_u16(reader)? as u64;
+                if run_end > u16::MAX as u64
+                    || (result.len() > start && high | run_start <= *result.last().unwrap())
+                    || result.len() - start + (run_end - run_start + 1) as usize > cardinality
+                {
+                    return Err(format!(
+                        "The Roaring stream is corrupted, the run container of key {} is not valid.",
+                        key
+                    ));
+                }
+                result.extend((run_start..=run_end).map(|low| high | low));
+            }
+        } else if cardinality <= MAX_ARRAY_CARDINALITY {
+            for _ in 0..cardinality {
+                let value = high | read_u16(reader)? as u64;
+                if result.len() > start && value <= *result.last().unwrap() {
+                    return Err(format!(
+                        "The Roaring stream is corrupted, the array container of key {} is not sorted.",
+                        key
+                    ));
+                }
+                result.push(value);
+            }
+        } else {
+            for word_index in 0..BITMAP_WORDS as u64 {
+                let mut word = u64::from_le_bytes(read_le(reader)?);
+                while word != 0 {
+                    result.push(high | word_index << WORD_SHIFT | word.trailing_zeros() as u64);
+                    word &= word - 1;
+                }
+            }
+        }
+        if result.len() - start != cardinality {
+            return Err(format!(
+                concat!(
+                    "The Roaring stream is corrupted, the container of key {} ",
+                    "has {} values but its cardinality is {}."
+                ),
+                key, result.len() - start, cardinality
+            ));
+        }
+    }
+    Ok(result)
+}
+
+/// Write the given **sorted** values in the portable Roaring serialization format,
+/// without run containers. The duplicated values are written once.
+pub(crate) fn write_roaring(writer: &mut impl Write, values: impl Iterator<Item = u64>) -> Result<(), String> {
+    // group the values by their high 16 bits
+    let mut containers: Vec<(u16, Vec<u16>)> = Vec::new();
+    let mut last_value = None;
+    for value in values {
+        if value > u32::MAX as u64 {
+            return Err(format!(
+                "The value {} cannot be stored in the Roaring format, which only supports u32.",
+                value
+            ));
+        }
+        if let Some(last_value) = last_value {
+            if value < last_value {
+                return Err(format!(
+                    "The values must be sorted, but the value {} follows the value {}.",
+                    value, last_value
+                ));
+            }
+            if value == last_value {
+                continue;
+            }
+        }
+        last_value = Some(value);
+        let key = (value >> 16) as u16;
+        match containers.last_mut() {
+            Some((last_key, lows)) if *last_key == key => lows.push(value as u16),
+            _ => containers.push((key, vec![value as u16])),
+        }
+    }
+
+    write_le(writer, &SERIAL_COOKIE_NO_RUNCONTAINER.to_le_bytes())?;
+    write_le(writer, &(containers.len() as u32).to_le_bytes())?;
+    for (key, lows) in &containers {
+        write_le(writer, &key.to_le_bytes())?;
+        write_le(writer, &((lows.len() - 1) as u16).to_le_bytes())?;
+    }
+    let mut offset = 8 + 8 * containers.len();
+    for (_, lows) in &containers {
+        write_le(writer, &(offset as u32).to_le_bytes())?;
+        offset += match lows.len() <= MAX_ARRAY_CARDINALITY {
+            true => 2 * lows.len(),
+            false => 8 * BITMAP_WORDS,
+        };
+    }
+    for (_, lows) in &containers {
+        if lows.len() <= MAX_ARRAY_CARDINALITY {
+            for low in lows {
+                write_le(writer, &low.to_le_bytes())?;
+            }
+        } else {
+            let mut words = [0_u64; BITMAP_WORDS];
+            for low in lows {
+                words[(*low as u64 >> WORD_SHIFT) as usize] |= 1 << (*low as u64 & WORD_MASK);
+            }
+            for word in words.iter() {
+                write_le(writer, &word.to_le_bytes())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+impl EliasFano<u64> {
+    /// Read a set written in the portable Roaring serialization format,
+    /// as used by the Java, Go and C implementations of Roaring bitmaps.
+    ///
+    /// ```
+    /// # use elias_fano_rust::EliasFano;
+    /// let ef = EliasFano::from_vec(&[1_u64, 5, 100_000]).unwrap();
+    /// let mut buffer = Vec::new();
+    /// ef.to_roaring(&mut buffer).unwrap();
+    /// assert_eq!(EliasFano::from_roaring(&mut buffer.as_slice()).unwrap(), ef);
+    /// ```
+    pub fn from_roaring(reader: &mut impl Read) -> Result<EliasFano<u64>, String> {
+        EliasFano::from_vec(&read_roaring(reader)?)
+    }
+
+    /// Write the set in the portable Roaring serialization format.
+    /// All the values must fit in a u32 and the duplicated values are written once.
+    pub fn to_roaring(&self, writer: &mut impl Write) -> Result<(), String> {
+        write_roaring(writer, self.iter())
+    }
+}
+
+impl SimpleSelect {
+    /// Read a set written in the portable Roaring serialization format
+    /// as the bitvector with the bits of its values set to one.
+    /// As in `from_vec`, the length is rounded up to a multiple of 64.
+    pub fn from_roaring(reader: &mut impl Read) -> Result<SimpleSelect, String> {
+        let values = read_roaring(reader)?;
+        let mut words = vec![0_u64; values.last().map(|last| (last >> WORD_SHIFT) as usize + 1).unwrap_or(0)];
+        for value in values {
+            words[(value >> WORD_SHIFT) as usize] |= 1 << (value & WORD_MASK);
+        }
+        Ok(SimpleSelect::from_vec(words))
+    }
+
+    /// Write the positions of the ones in the portable Roaring serialization format.
+    /// All the ones must be in the first 2^32 bits.
+    pub fn to_roaring(&self, writer: &mut impl Write) -> Result<(), String> {
+        write_roaring(writer, self.iter())
+    }
+}
@@ -0,0 +1,131 @@
+use super::*;
+use alloc::collections::BTreeSet;
+use core::ops::Range;
+use rand::Rng;
+
+/// Return `k` distinct indices in [0, `n`) chosen uniformly at random, sorted.
+///
+/// This is Floyd's algorithm, so it needs `k` random numbers and memory
+/// proportional to `k`, not to `n`.
+fn sample_indices(n: u64, k: u64, rng: &mut impl Rng) -> BTreeSet<u64> {
+    let mut indices = BTreeSet::new();
+    for j in n - k..n {
+        let index = rng.gen_range(0, j + 1);
+        if !indices.insert(index) {
+            indices.insert(j);
+        }
+    }
+    indices
+}
+
+impl<T: UnsignedInteger> EliasFano<T> {
+    /// Return an element chosen uniformly at random, or None if the set is empty.
+    /// Duplicated values are weighted by the number of times they appear.
+    ///
+    /// ```rust
+    /// # use elias_fano_rust::EliasFano;
+    /// # use rand::{SeedableRng, rngs::SmallRng};
+    /// let ef = EliasFano::from_vec(&[5_u64, 8, 8, 15, 32]).unwrap();
+    /// let mut rng = SmallRng::seed_from_u64(0xbad5eed);
+    /// assert!(ef.contains(ef.sample(&mut rng).unwrap()));
+    /// ```
+    #[inline]
+    pub fn sample(&self, rng: &mut impl Rng) -> Option<T> {
+        match self.is_empty() {
+            true => None,
+            false => Some(self.unchecked_select(rng.gen_range(0, self.current_number_of_elements))),
+        }
+    }
+
+    /// Return an element in the given range of values chosen uniformly at
+    /// random among the ones in the range, or None if there are none.
+    /// The range is converted into a range of indices with two ranks.
+    ///
+    /// ```rust
+    /// # use elias_fano_rust::EliasFano;
+    /// # use rand::{SeedableRng, rngs::SmallRng};
+    /// let ef = EliasFano::from_vec(&[5_u64, 8, 8, 15, 32]).unwrap();
+    /// let mut rng = SmallRng::seed_from_u64(0xbad5eed);
+    /// assert_eq!(ef.sample_in_range(9..16, &mut rng), Some(15));
+    /// assert_eq!(ef.sample_in_range(9..15, &mut rng), None);
+    /// ```
+    #[inline]
+    pub fn sample_in_range(&self, range: Range<T>, rng: &mut impl Rng) -> Option<T> {
+        if range.start >= range.end {
+            return None;
+        }
+        let start = self.unchecked_rank(range.start);
+        let end = self.unchecked_rank(range.end);
+        match start < end {
+            true => Some(self.unchecked_select(rng.gen_range(start, end))),
+            false => None,
+        }
+    }
+
+    /// Return `k` elements chosen uniformly at random without replacement,
+    /// in increasing order. Without replacement refers to the positions, so
+    /// a duplicated value can be returned as many times as it appears.
+    ///
+    /// ```rust
+    /// # use elias_fano_rust::EliasFano;
+    /// # use rand::{SeedableRng, rngs::SmallRng};
+    /// let ef = EliasFano::from_vec(&[5_u64, 8, 8, 15, 32]).unwrap();
+    /// let mut rng = SmallRng::seed_from_u64(0xbad5eed);
+    /// assert_eq!(ef.sample_k_without_replacement(5, &mut rng).unwrap(), vec![5, 8, 8, 15, 32]);
+    /// assert!(ef.sample_k_without_replacement(6, &mut rng).is_err());
+    /// ```
+    pub fn sample_k_without_replacement(&self, k: usize, rng: &mut impl Rng) -> Result<Vec<T>, String> {
+        if k as u64 > self.current_number_of_elements {
+            return Err(format!(
+                "Cannot sample {} elements without replacement from a set of {} elements.",
+                k, self.current_number_of_elements
+            ));
+        }
+        Ok(sample_indices(self.current_number_of_elements, k as u64, rng)
+            .into_iter()
+            .map(|index| self.unchecked_select(index))
+            .collect())
+    }
+}
+
+impl SimpleSelect {
+    /// Return the position of a bit set to one chosen uniformly at random,
+    /// or None if there are no ones.
+    #[inline]
+    pub fn sample(&self, rng: &mut impl Rng) -> Option<u64> {
+        match self.count_ones() {
+            0 => None,
+            ones => Some(self.unchecked_select1(rng.gen_range(0, ones))),
+        }
+    }
+
+    /// Return the position of a bit set to one in the given range of
+    /// positions, chosen uniformly at random, or None if there are none.
+    #[inline]
+    pub fn sample_in_range(&self, range: Range<u64>, rng: &mut impl Rng) -> Option<u64> {
+        if range.start >= range.end {
+            return None;
+        }
+        let start = self.rank1(range.start);
+        let end = self.rank1(range.end);
+        match start < end {
+            true => Some(self.unchecked_select1(rng.gen_range(start, end))),
+            false => None,
+        }
+    }
+
+    /// Return the positions of `k` distinct bits set to one chosen uniformly
+    /// at random, in increasing order.
+    pub fn sample_k_without_replacement(&self, k: usize, rng: &mut impl Rng) -> Result<Vec<u64>, String> {
+        if k as u64 > self.count_ones() {
+            return Err(format!(
+                "Cannot sample {} ones without replacement from a bitvector with {} ones.",
+                k, self.count_ones()
+            ));
+        }
+        Ok(sample_indices(self.count_ones(), k as u64, rng)
+            .into_iter()
+            .map(|index| self.unchecked_select1(index))
+            .collect())
+    }
+}
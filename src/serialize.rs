@@ -0,0 +1,365 @@
+use super::*;
+use std::io::{Read, Write};
+
+/// Magic number written at the start of every serialized EliasFano,
+/// it's used to detect files which are not EliasFano or that were written
+/// by an incompatible version of the crate.
+pub const SERIALIZATION_MAGIC: u64 = u64::from_le_bytes(*b"EFRS0001");
+
+/// Magic number written at the start of the serialized EliasFano which
+/// are followed by a checksum of their data, see `serialize_with_checksum`.
+pub const CHECKSUM_SERIALIZATION_MAGIC: u64 = u64::from_le_bytes(*b"EFRC0001");
+
+/// Offset basis and prime of the 64 bits FNV-1a hash used as checksum.
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+#[inline]
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+/// Writer which computes the checksum of the bytes written through it.
+struct ChecksumWriter<'a, W: Write> {
+    inner: &'a mut W,
+    checksum: u64,
+}
+
+impl<'a, W: Write> Write for ChecksumWriter<'a, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.checksum = fnv1a(self.checksum, &buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reader which computes the checksum of the bytes read through it.
+struct ChecksumReader<'a, R: Read> {
+    inner: &'a mut R,
+    checksum: u64,
+}
+
+impl<'a, R: Read> Read for ChecksumReader<'a, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.checksum = fnv1a(self.checksum, &buf[..read]);
+        Ok(read)
+    }
+}
+
+#[inline]
+pub(crate) fn write_u64(writer: &mut impl Write, value: u64) -> Result<(), String> {
+    writer.write_all(&value.to_le_bytes())
+        .map_err(|e| format!("Cannot write to the serialization stream: {}", e))
+}
+
+#[inline]
+pub(crate) fn read_u64(reader: &mut impl Read) -> Result<u64, String> {
+    let mut buffer = [0_u8; 8];
+    reader.read_exact(&mut buffer)
+        .map_err(|e| format!("Cannot read from the serialization stream: {}", e))?;
+    Ok(u64::from_le_bytes(buffer))
+}
+
+/// Write a value of any supported type as two words, the lower 64 bits
+/// followed by the higher 64 bits.
+#[inline]
+pub(crate) fn write_value<T: UnsignedInteger>(writer: &mut impl Write, value: T) -> Result<(), String> {
+    let value = value.to_u128();
+    write_u64(writer, value as u64)?;
+    write_u64(writer, (value >> 64) as u64)
+}
+
+/// Read a value written by `write_value`.
+#[inline]
+pub(crate) fn read_value<T: UnsignedInteger>(reader: &mut impl Read) -> Result<T, String> {
+    let lower = read_u64(reader)? as u128;
+    let higher = read_u64(reader)? as u128;
+    let value = (higher << 64) | lower;
+    if value > T::MAX.to_u128() {
+        return Err(format!(
+            "The serialized value {} does not fit in {} bits.",
+            value, T::BITS
+        ));
+    }
+    Ok(T::from_u128(value))
+}
+
+/// The header of a serialized EliasFano, which follows the magic number,
+/// this is shared with the external builder which streams the rest of the data.
+pub(crate) struct EliasFanoHeader<T: UnsignedInteger> {
+    pub(crate) universe: T,
+    pub(crate) min_value: T,
+    pub(crate) strict: bool,
+    pub(crate) number_of_elements: u64,
+    pub(crate) current_number_of_elements: u64,
+    pub(crate) low_bit_count: u64,
+    pub(crate) last_value: T,
+}
+
+impl<T: UnsignedInteger> EliasFanoHeader<T> {
+    /// Write the header, preceded by the number of bits of the type of the values.
+    pub(crate) fn write(&self, writer: &mut impl Write) -> Result<(), String> {
+        write_u64(writer, T::BITS)?;
+        write_value(writer, self.universe)?;
+        write_value(writer, self.min_value)?;
+        write_u64(writer, self.strict as u64)?;
+        write_u64(writer, self.number_of_elements)?;
+        write_u64(writer, self.current_number_of_elements)?;
+        write_u64(writer, self.low_bit_count)?;
+        write_value(writer, self.last_value)
+    }
+}
+
+/// Write the length of the slice followed by its words.
+pub(crate) fn write_vec(writer: &mut impl Write, values: &[u64]) -> Result<(), String> {
+    write_u64(writer, values.len() as u64)?;
+    for value in values {
+        write_u64(writer, *value)?;
+    }
+    Ok(())
+}
+
+/// Read a vector written by `write_vec`.
+pub(crate) fn read_vec(reader: &mut impl Read) -> Result<Vec<u64>, String> {
+    let len = read_u64(reader)? as usize;
+    // the length is not trusted, so that a corrupted one fails on the read
+    // of the missing words instead of on the allocation
+    let mut result = Vec::with_capacity(len.min(1 << 20));
+    for _ in 0..len {
+        result.push(read_u64(reader)?);
+    }
+    Ok(result)
+}
+
+impl SimpleSelect {
+    /// Write the bitvector and its indices to the given writer.
+    ///
+    /// The format is a sequence of little-endian u64:
+    /// `len`, `number_of_ones`, `number_of_zeros` and then the high-bits,
+    /// the zeros index and the ones index, each one prefixed by its length.
+    pub fn serialize(&self, writer: &mut impl Write) -> Result<(), String> {
+        write_u64(writer, self.len)?;
+        write_u64(writer, self.number_of_ones)?;
+        write_u64(writer, self.number_of_zeros)?;
+        write_vec(writer, &self.high_bits)?;
+        write_vec(writer, &self.high_bits_index_zeros)?;
+        write_vec(writer, &self.high_bits_index_ones)
+    }
+
+    /// Read a SimpleSelect written by `serialize`.
+    ///
+    /// All the invariants are checked with `validate`, so that a corrupted
+    /// stream cannot build a bitvector whose queries read out of bounds.
+    pub fn deserialize(reader: &mut impl Read) -> Result<SimpleSelect, String> {
+        let result = SimpleSelect::deserialize_parts(reader)?;
+        result.validate().into_result()?;
+        Ok(result)
+    }
+
+    /// Read the fields of a SimpleSelect without checking their invariants.
+    fn deserialize_parts(reader: &mut impl Read) -> Result<SimpleSelect, String> {
+        let len = read_u64(reader)?;
+        let number_of_ones = read_u64(reader)?;
+        let number_of_zeros = read_u64(reader)?;
+        let high_bits = read_vec(reader)?;
+        let high_bits_index_zeros = read_vec(reader)?;
+        let high_bits_index_ones = read_vec(reader)?;
+
+        Ok(SimpleSelect{
+            high_bits,
+            high_bits_index_zeros,
+            high_bits_index_ones,
+            number_of_ones,
+            number_of_zeros,
+            len,
+        })
+    }
+}
+
+impl<T: UnsignedInteger> EliasFano<T> {
+    /// Write the EliasFano to the given writer.
+    ///
+    /// The format is a sequence of little-endian u64:
+    /// the magic number, the number of bits of the type of the values,
+    /// `universe`, `min_value`, `strict`, `number_of_elements`, `current_number_of_elements`,
+    /// `low_bit_count`, `last_value`, then the low-bits prefixed by
+    /// their length and finally the serialized high-bits SimpleSelect.
+    /// The `universe`, `min_value` and `last_value` are written as two words
+    /// so that the format is the same for all the types.
+    ///
+    /// ```
+    /// # use elias_fano_rust::EliasFano;
+    /// let ef = EliasFano::from_vec(&[5_u64, 8, 8, 15, 32]).unwrap();
+    /// let mut buffer = Vec::new();
+    /// ef.serialize(&mut buffer).unwrap();
+    /// assert_eq!(ef, EliasFano::deserialize(&mut buffer.as_slice()).unwrap());
+    /// ```
+    pub fn serialize(&self, writer: &mut impl Write) -> Result<(), String> {
+        write_u64(writer, SERIALIZATION_MAGIC)?;
+        self.serialize_body(writer)
+    }
+
+    /// Write the EliasFano to the given writer in the format of `serialize`,
+    /// but with the `CHECKSUM_SERIALIZATION_MAGIC` and followed by the
+    /// 64 bits FNV-1a hash of the data after the magic number.
+    /// The checksum is verified by `deserialize`.
+    ///
+    /// ```
+    /// # use elias_fano_rust::EliasFano;
+    /// let ef = EliasFano::from_vec(&[5_u64, 8, 8, 15, 32]).unwrap();
+    /// let mut buffer = Vec::new();
+    /// ef.serialize_with_checksum(&mut buffer).unwrap();
+    /// assert_eq!(ef, EliasFano::deserialize(&mut buffer.as_slice()).unwrap());
+    /// buffer[20] ^= 1;
+    /// assert!(EliasFano::<u64>::deserialize(&mut buffer.as_slice()).is_err());
+    /// ```
+    pub fn serialize_with_checksum(&self, writer: &mut impl Write) -> Result<(), String> {
+        write_u64(writer, CHECKSUM_SERIALIZATION_MAGIC)?;
+        let mut checksum_writer = ChecksumWriter{inner: writer, checksum: FNV_OFFSET_BASIS};
+        self.serialize_body(&mut checksum_writer)?;
+        let checksum = checksum_writer.checksum;
+        write_u64(writer, checksum)
+    }
+
+    /// Write everything but the magic number.
+    fn serialize_body(&self, writer: &mut impl Write) -> Result<(), String> {
+        EliasFanoHeader{
+            universe: self.universe,
+            min_value: self.min_value,
+            strict: self.strict,
+            number_of_elements: self.number_of_elements,
+            current_number_of_elements: self.current_number_of_elements,
+            low_bit_count: self.low_bit_count,
+            last_value: self.last_value,
+        }.write(writer)?;
+        write_vec(writer, &self.low_bits)?;
+        self.high_bits.serialize(writer)
+    }
+
+    /// Read an EliasFano written by `serialize` or `serialize_with_checksum`,
+    /// in the latter case the checksum is verified.
+    pub fn deserialize(reader: &mut impl Read) -> Result<EliasFano<T>, String> {
+        let magic = read_u64(reader)?;
+        match magic {
+            SERIALIZATION_MAGIC => EliasFano::deserialize_body(reader),
+            CHECKSUM_SERIALIZATION_MAGIC => {
+                let mut checksum_reader = ChecksumReader{inner: reader, checksum: FNV_OFFSET_BASIS};
+                let result = EliasFano::deserialize_body(&mut checksum_reader)?;
+                let checksum = checksum_reader.checksum;
+                let expected_checksum = read_u64(reader)?;
+                if checksum != expected_checksum {
+                    return Err(format!(
+                        concat!(
+                            "The serialized EliasFano is corrupted, the checksum of its data ",
+                            "is {:x} while the stored one is {:x}."
+                        ),
+                        checksum, expected_checksum
+                    ));
+                }
+                Ok(result)
+            }
+            _ => Err(format!(
+                concat!(
+                    "The given stream does not contain a serialized EliasFano ",
+                    "the magic number read is {:x} while the expected one is {:x}."
+                ),
+                magic, SERIALIZATION_MAGIC
+            )),
+        }
+    }
+
+    /// Read everything but the magic number.
+    fn deserialize_body(reader: &mut impl Read) -> Result<EliasFano<T>, String> {
+        let bits = read_u64(reader)?;
+        if bits != T::BITS {
+            return Err(format!(
+                concat!(
+                    "The serialized EliasFano stores values of {} bits ",
+                    "but it's being loaded as values of {} bits."
+                ),
+                bits, T::BITS
+            ));
+        }
+        let universe = read_value(reader)?;
+        let min_value: T = read_value(reader)?;
+        let strict = match read_u64(reader)? {
+            0 => false,
+            1 => true,
+            flag => return Err(format!(
+                "The serialized EliasFano is corrupted, its strict flag is {}.",
+                flag
+            )),
+        };
+        let number_of_elements = read_u64(reader)?;
+        let current_number_of_elements = read_u64(reader)?;
+        let low_bit_count = read_u64(reader)?;
+        let last_value: T = read_value(reader)?;
+
+        if low_bit_count > T::BITS {
+            return Err(format!(
+                "The serialized EliasFano is corrupted, it has {} low bits.",
+                low_bit_count
+            ));
+        }
+
+        if current_number_of_elements > 0 && (last_value < min_value || last_value > universe) {
+            return Err(format!(
+                concat!(
+                    "The serialized EliasFano is corrupted, its last value {} ",
+                    "is not in the range [{}, {}]."
+                ),
+                last_value, min_value, universe
+            ));
+        }
+
+        if strict && current_number_of_elements > 0
+            && (last_value - min_value).to_u128() < (current_number_of_elements - 1) as u128 {
+            return Err(format!(
+                concat!(
+                    "The serialized EliasFano is corrupted, there cannot be {} strictly ",
+                    "increasing values between {} and {}."
+                ),
+                current_number_of_elements, min_value, last_value
+            ));
+        }
+
+        let low_bits = read_vec(reader)?;
+        // the high-bits are checked by the validation of the whole structure
+        let high_bits = SimpleSelect::deserialize_parts(reader)?;
+
+        let result = EliasFano{
+            low_bits,
+            high_bits,
+            universe,
+            min_value,
+            strict,
+            number_of_elements,
+            low_bit_count,
+            low_bit_mask: T::mask(low_bit_count),
+            last_high_value: match current_number_of_elements {
+                0 => 0,
+                _ => {
+                    let mut encoded_value = last_value - min_value;
+                    if strict {
+                        encoded_value = encoded_value - T::from_u64(current_number_of_elements - 1);
+                    }
+                    encoded_value.shift_right(low_bit_count).to_u64()
+                },
+            },
+            last_value,
+            last_index: current_number_of_elements,
+            current_number_of_elements,
+        };
+        result.validate().into_result()?;
+        Ok(result)
+    }
+}
@@ -16,21 +16,123 @@ impl SimpleSelect {
     pub fn len(&self) -> u64 {
         self.len
     }
+
+    /// Return the words of the bitvector, the bit of position `i` is
+    /// the bit `i % 64` of the word `i / 64`.
+    #[inline]
+    pub fn high_bits_words(&self) -> &[u64] {
+        &self.high_bits
+    }
+
+    /// Return the positions of every `2^INDEX_SHIFT`-th bit set to one.
+    #[inline]
+    pub fn high_bits_index_ones(&self) -> &[u64] {
+        &self.high_bits_index_ones
+    }
+
+    /// Return the positions of every `2^INDEX_SHIFT`-th bit set to zero.
+    #[inline]
+    pub fn high_bits_index_zeros(&self) -> &[u64] {
+        &self.high_bits_index_zeros
+    }
 }
 
 
-/// # Core functionalities
+/// # Checked queries
 impl SimpleSelect {
     /// Returns the value of the bit of position `index`.
+    ///
+    /// # Panics
+    /// If `index` is not smaller than the length.
+    #[inline]
     pub fn get(&self, index: u64) -> bool {
+        assert!(
+            index < self.len,
+            "Given index {} is out of bound on a bitvector of length {}.",
+            index, self.len
+        );
+        self.unchecked_get(index)
+    }
+
+    /// Returns the value of the bit of position `index`, if present.
+    #[inline]
+    pub fn try_get(&self, index: u64) -> Option<bool> {
+        match index < self.len {
+            true => Some(self.unchecked_get(index)),
+            false => None,
+        }
+    }
+
+    /// Returns the position of the `index`-th bit set to one.
+    ///
+    /// # Panics
+    /// If `index` is not smaller than the number of ones.
+    #[inline]
+    pub fn select1(&self, index: u64) -> u64 {
+        assert!(
+            index < self.number_of_ones,
+            "Given index {} is out of bound on a bitvector with {} ones.",
+            index, self.number_of_ones
+        );
+        self.unchecked_select1(index)
+    }
+
+    /// Returns the position of the `index`-th bit set to one, if present.
+    ///
+    /// ```
+    /// # use elias_fano_rust::SimpleSelect;
+    /// let bitmap = SimpleSelect::from_vec(vec![0b1010]);
+    /// assert_eq!(bitmap.try_select1(1), Some(3));
+    /// assert_eq!(bitmap.try_select1(2), None);
+    /// assert_eq!(bitmap.try_select0(0), Some(0));
+    /// assert_eq!(bitmap.try_get(64), None);
+    /// ```
+    #[inline]
+    pub fn try_select1(&self, index: u64) -> Option<u64> {
+        match index < self.number_of_ones {
+            true => Some(self.unchecked_select1(index)),
+            false => None,
+        }
+    }
+
+    /// Returns the position of the `index`-th bit set to zero.
+    ///
+    /// # Panics
+    /// If `index` is not smaller than the number of zeros.
+    #[inline]
+    pub fn select0(&self, index: u64) -> u64 {
+        assert!(
+            index < self.number_of_zeros,
+            "Given index {} is out of bound on a bitvector with {} zeros.",
+            index, self.number_of_zeros
+        );
+        self.unchecked_select0(index)
+    }
+
+    /// Returns the position of the `index`-th bit set to zero, if present.
+    #[inline]
+    pub fn try_select0(&self, index: u64) -> Option<u64> {
+        match index < self.number_of_zeros {
+            true => Some(self.unchecked_select0(index)),
+            false => None,
+        }
+    }
+}
+
+/// # Core functionalities
+impl SimpleSelect {
+    /// Returns the value of the bit of position `index` without checking
+    /// that it's smaller than the length.
+    pub fn unchecked_get(&self, index: u64) -> bool {
         let word_idx = index >> WORD_SHIFT;
         let bit_idx = index & WORD_MASK;
         let bit_value = (self.high_bits[word_idx as usize] >> bit_idx) & 1;
         bit_value == 1
     }
 
-    /// Returns the position of the `index`-th bit set to one.
-    pub fn select1(&self, index: u64) -> u64 {
+    /// Returns the position of the `index`-th bit set to one without
+    /// checking that it's smaller than the number of ones.
+    pub fn unchecked_select1(&self, index: u64) -> u64 {
         // use the index to find in which block the value is
         let mut reminder_to_scan = index & INDEX_MASK;
         let index_idx = index >> INDEX_SHIFT;
@@ -68,8 +170,9 @@ impl SimpleSelect {
         (block_id * WORD_SIZE) + code.trailing_zeros() as u64
     }
 
-    /// Returns the position of the `index`-th bit set to zero.
-    pub fn select0(&self, index: u64) -> u64 {
+    /// Returns the position of the `index`-th bit set to zero without
+    /// checking that it's smaller than the number of zeros.
+    pub fn unchecked_select0(&self, index: u64) -> u64 {
         // use the index to find in which block the value is
         let mut reminder_to_scan = index & INDEX_MASK;
         let index_idx = index >> INDEX_SHIFT;
@@ -236,5 +339,43 @@ impl SimpleSelect {
             }
         }
     }
-    
+
+    /// Return the number of bits set to one in the given range of positions.
+    ///
+    /// This is `rank1(end) - rank1(start)`, but when the range is short the
+    /// words between the two ends are popcnt-ed directly, without searching
+    /// the index twice.
+    ///
+    /// ```rust
+    /// # use elias_fano_rust::SimpleSelect;
+    /// let bitmap = SimpleSelect::from_vec(vec![0b1011, u64::MAX]);
+    /// assert_eq!(bitmap.count_ones_in_range(1..4), 2);
+    /// assert_eq!(bitmap.count_ones_in_range(0..200), 67);
+    /// ```
+    pub fn count_ones_in_range(&self, range: Range<u64>) -> u64 {
+        let start = range.start;
+        let end = range.end.min(self.len());
+        if start >= end {
+            return 0;
+        }
+        let start_word = start >> WORD_SHIFT;
+        let end_word = (end - 1) >> WORD_SHIFT;
+        // each rank searches the index and scans from a sample, so up to a
+        // quantum of bits it's cheaper to popcnt the range
+        if end_word - start_word > INDEX_MASK >> WORD_SHIFT {
+            return self.rank1(end) - self.rank1(start);
+        }
+
+        // clean the bits before the start and after the end
+        let start_mask = u64::MAX << (start & WORD_MASK);
+        let end_mask = u64::MAX >> (WORD_MASK - ((end - 1) & WORD_MASK));
+        if start_word == end_word {
+            return (self.high_bits[start_word as usize] & start_mask & end_mask).count_ones() as u64;
+        }
+        let mut result = (self.high_bits[start_word as usize] & start_mask).count_ones() as u64;
+        for word in &self.high_bits[start_word as usize + 1..end_word as usize] {
+            result += word.count_ones() as u64;
+        }
+        result + (self.high_bits[end_word as usize] & end_mask).count_ones() as u64
+    }
 }
@@ -1,5 +1,4 @@
 use super::*;
-use std::intrinsics::unlikely;
 
 impl<'a> IntoIterator for &'a SimpleSelect {
     type Item = u64;
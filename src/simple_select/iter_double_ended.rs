@@ -1,6 +1,7 @@
 use super::*;
-use std::intrinsics::unlikely;
+#[cfg(feature = "std")]
 use rayon::prelude::*;
+#[cfg(feature = "std")]
 use rayon::iter::plumbing::{
     bridge_unindexed, 
     UnindexedProducer,
@@ -34,8 +35,8 @@ pub struct SimpleSelectDobuleEndedIterator<'a> {
     len: usize,
 }
 
-impl<'a> std::fmt::Debug for SimpleSelectDobuleEndedIterator<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<'a> core::fmt::Debug for SimpleSelectDobuleEndedIterator<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("SimpleSelectDobuleEndedIterator")
             .field("start_code", &format!("{:064b}", self.start_code))
             .field("start_index", &self.start_index)
@@ -49,18 +50,12 @@ impl<'a> std::fmt::Debug for SimpleSelectDobuleEndedIterator<'a> {
 
 impl<'a> SimpleSelectDobuleEndedIterator<'a> {
     pub fn new(father: &'a SimpleSelect) -> SimpleSelectDobuleEndedIterator<'a> {
-        SimpleSelectDobuleEndedIterator{
-            start_code: *father.high_bits.get(0).unwrap_or(&0),
-            start_index: 0,
-            end_code: *father.high_bits.last().unwrap_or(&0),
-            end_index: father.high_bits.len().saturating_sub(1),
-            len: father.count_ones() as _,
-            father, 
-        }
+        SimpleSelectDobuleEndedIterator::new_in_range(father, 0..father.len())
     }
 
     pub fn new_in_range(father: &'a SimpleSelect, range: Range<u64>) -> SimpleSelectDobuleEndedIterator<'a> {
-        if range.start >= father.len() {
+        let end = range.end.min(father.len());
+        if range.start >= end {
             return SimpleSelectDobuleEndedIterator{
                 start_code: 0,
                 start_index: 0,
@@ -70,45 +65,30 @@ impl<'a> SimpleSelectDobuleEndedIterator<'a> {
                 father, 
             };
         }
-        // if the range starts and ends inside the same word of memory we need
-        // special logic to clear the bits and avoid duplication of values
-        if (range.start >> WORD_SHIFT) == (range.end >> WORD_SHIFT) {
-            let idx = (range.start >> WORD_SHIFT) as usize;
-            let mut code = father.high_bits[idx];
-
-            // clean the higher and lwoer bits according to the range values
-            code &= u64::MAX << (range.start & WORD_MASK);
-            code &= !(!0_u64 << (64 - range.start & WORD_MASK));
-            
-            return SimpleSelectDobuleEndedIterator{
-                len: code.count_ones() as usize,
-                start_code: code,
-                start_index: idx,
-                end_code: 0,
-                end_index: idx,
-                father, 
-            };
-        }
-        
-        // general well behaved case
-        let start_index = range.start >> WORD_SHIFT;
-        let start_in_word_reminder = range.start & WORD_MASK;
-        let mut start_code = father.high_bits[start_index as usize];
-        start_code &= u64::MAX << start_in_word_reminder;
 
-        let end_index = range.end >> WORD_SHIFT;
-        let end_in_word_reminder = range.end & WORD_MASK;
-        let mut end_code = father.high_bits[end_index as usize];
-        end_code &= !(!0_u64 << end_in_word_reminder);
+        // the words of the first and of the last bit of the range
+        let start_index = (range.start >> WORD_SHIFT) as usize;
+        let end_index = ((end - 1) >> WORD_SHIFT) as usize;
+
+        // clean the bits before the start and after the end
+        let mut start_code = father.high_bits[start_index] & (u64::MAX << (range.start & WORD_MASK));
+        let end_mask = u64::MAX >> (WORD_MASK - ((end - 1) & WORD_MASK));
+
+        // if the range starts and ends inside the same word of memory, the
+        // word is kept only in the start code to avoid duplication of values
+        let end_code = if start_index == end_index {
+            start_code &= end_mask;
+            0
+        } else {
+            father.high_bits[end_index] & end_mask
+        };
 
         SimpleSelectDobuleEndedIterator{
             start_code,
-            start_index: start_index as usize,
+            start_index,
             end_code,
-            end_index: end_index as usize,
-            len: (
-                father.rank1(range.end) - father.rank1(range.start)
-            ) as usize,
+            end_index,
+            len: father.count_ones_in_range(range.start..end) as usize,
             father, 
         }
     }
@@ -123,25 +103,17 @@ impl<'a> Iterator for SimpleSelectDobuleEndedIterator<'a> {
         while unlikely(self.start_code == 0) {
             let tmp_idx = self.start_index + 1;
             if unlikely(tmp_idx >= self.end_index) {
-                if unlikely(self.end_code == 0) {
+                // the words in the middle are finished, so continue on the
+                // word on which the other iter is working, if any
+                if unlikely(tmp_idx > self.end_index || self.end_code == 0) {
                     return None;
                 }
-                
-                // get the index of the first one (we are guaranteed to have
-                // at least one bit set to 1)
-                let t = self.end_code.trailing_zeros();
-
-                // clear it from the current code
-                self.end_code &= self.end_code - 1;
-
-                // compute the result value
-                let result = (tmp_idx as u64 * WORD_SIZE) + t as u64;
-                self.len -= 1;
-                return Some(result);
+                self.start_code = self.end_code;
+                self.end_code = 0;
+            } else {
+                self.start_code = self.father.high_bits[tmp_idx];
             }
-
             self.start_index = tmp_idx;
-            self.start_code = self.father.high_bits[self.start_index]
         }
 
         // get the index of the first one (we are guaranteed to have
@@ -168,10 +140,9 @@ impl<'a> DoubleEndedIterator for SimpleSelectDobuleEndedIterator<'a> {
     #[inline]
     fn next_back(&mut self) -> Option<Self::Item> {
         while unlikely(self.end_code == 0) {
-            let tmp_idx = self.end_index.saturating_sub(1);
             // if we reach the index of the start, we should finish the word
             // on which the other iter is already working
-            if unlikely(self.start_index >= tmp_idx) {
+            if unlikely(self.end_index <= self.start_index + 1) {
                 if unlikely(self.start_code == 0) {
                     return None;
                 }
@@ -184,13 +155,13 @@ impl<'a> DoubleEndedIterator for SimpleSelectDobuleEndedIterator<'a> {
                 self.start_code ^= 1 << t;
 
                 // compute the result value
-                let result = (tmp_idx as u64 * WORD_SIZE) + t as u64;
+                let result = (self.start_index as u64 * WORD_SIZE) + t as u64;
                 self.len -= 1;
                 return Some(result);
             }
 
             // iter over the highbits
-            self.end_index = tmp_idx;
+            self.end_index -= 1;
             self.end_code = self.father.high_bits[self.end_index]
         }
 
@@ -214,6 +185,7 @@ impl<'a> DoubleEndedIterator for SimpleSelectDobuleEndedIterator<'a> {
 /// which is slightly faster. 
 ///
 /// Thus, this trait is not really needed, but we have it ¯\_(ツ)_/¯ .
+#[cfg(feature = "std")]
 impl<'a> UnindexedProducer for SimpleSelectDobuleEndedIterator<'a> {
     type Item = u64;
 
@@ -233,7 +205,7 @@ impl<'a> UnindexedProducer for SimpleSelectDobuleEndedIterator<'a> {
         // Compute the middle 1 in the current iterator
         let middle_point = start_rank + (self.len / 2);
         // Find it's index, so we can split the iterator exactly in half
-        let middle_bit_index = self.father.select1(middle_point as u64);
+        let middle_bit_index = self.father.unchecked_select1(middle_point as u64);
         let code = self.father.high_bits[middle_bit_index as usize];
         let inword_offset = middle_bit_index & WORD_MASK;
 
@@ -21,7 +21,7 @@ impl SimpleSelectMemoryStats {
 impl SimpleSelect {
     /// Return the memory used in bytes
     pub fn size(&self) -> SimpleSelectMemoryStats {
-        use std::mem::size_of;
+        use core::mem::size_of;
         SimpleSelectMemoryStats {
             metadata: 3 * size_of::<u64>(),
             high_bits:  (self.high_bits.capacity() * size_of::<u64>()) + size_of::<Vec<u64>>(),
@@ -31,6 +31,18 @@ impl SimpleSelect {
         }
     }
 
+    /// Return the memory in bytes that a SimpleSelect of `len` bits with
+    /// `number_of_ones` bits set to one uses once shrinked, without building it.
+    pub fn estimate_size(len: u64, number_of_ones: u64) -> usize {
+        use core::mem::size_of;
+        let words = (len + WORD_MASK) >> WORD_SHIFT;
+        let index_ones = (number_of_ones + INDEX_MASK) >> INDEX_SHIFT;
+        let index_zeros = (len - number_of_ones + INDEX_MASK) >> INDEX_SHIFT;
+        3 * size_of::<u64>()
+            + 3 * size_of::<Vec<u64>>()
+            + (words + index_ones + index_zeros) as usize * size_of::<u64>()
+    }
+
     /// Reduces the memory allocated to the minimum needed.
     pub fn shrink_to_fit(&mut self) {
         self.high_bits.shrink_to_fit();
@@ -1,5 +1,6 @@
 use super::*;
-use std::ops::Range;
+use core::ops::Range;
+#[cfg(feature = "std")]
 use std::sync::Arc;
 
 mod memory;
@@ -14,12 +15,12 @@ mod getters;
 ///  Structure with index inspired by Vigna's simple select
 /// This structure is efficient for **DENSE** bitvectors
 pub struct SimpleSelect {
-    pub high_bits: Vec<u64>,
-    pub high_bits_index_zeros: Vec<u64>,
-    pub high_bits_index_ones: Vec<u64>,
-    pub number_of_ones: u64,
-    pub number_of_zeros: u64,
-    pub len: u64,
+    pub(crate) high_bits: Vec<u64>,
+    pub(crate) high_bits_index_zeros: Vec<u64>,
+    pub(crate) high_bits_index_ones: Vec<u64>,
+    pub(crate) number_of_ones: u64,
+    pub(crate) number_of_zeros: u64,
+    pub(crate) len: u64,
 }
 
 impl PartialEq for SimpleSelect {
@@ -83,11 +84,36 @@ impl SimpleSelect {
         self.len += 1;
     }
 
+    /// Create a SimpleSelect from its parts without building the indices,
+    /// for example to load it from a custom format.
+    ///
+    /// # Safety
+    /// The parts must satisfy all the invariants checked by `validate`:
+    /// `len` is the sum of `number_of_ones` and `number_of_zeros`, the words
+    /// are exactly the ones needed for `len` bits, the bits after `len` are zero
+    /// and the indices contain the position of every `2^INDEX_SHIFT`-th one
+    /// and zero. Otherwise `select1`, `select0` and `rank1` can read out of bounds,
+    /// which is undefined behaviour with the `unsafe` feature.
+    pub unsafe fn from_raw_parts(
+        high_bits: Vec<u64>,
+        high_bits_index_zeros: Vec<u64>,
+        high_bits_index_ones: Vec<u64>,
+        number_of_ones: u64,
+        number_of_zeros: u64,
+        len: u64,
+    ) -> SimpleSelect {
+        SimpleSelect{
+            high_bits,
+            high_bits_index_zeros,
+            high_bits_index_ones,
+            number_of_ones,
+            number_of_zeros,
+            len,
+        }
+    }
+
     /// Take the given bit-vector and build the indices on it.
     pub fn from_vec(bitvector: Vec<u64>) -> SimpleSelect {
-
-        let bitvector = Arc::new(bitvector);
-
         // The following two steps are independant so we could parallelize them
         // using two separate threads
         // moreover, if we know in advance the number of ones and zeros in the bitvector
@@ -95,49 +121,21 @@ impl SimpleSelect {
         // from the start to the middle, and one thread that build from the end
         // to the middle
         ////////////////////////////////////////////////////////////////////////
-        let ones_bitvector_copy = bitvector.clone();
-        let ones_counter = std::thread::spawn(move || {
-            let mut high_bits_index_ones = Vec::with_capacity(ones_bitvector_copy.len() >> INDEX_SHIFT);
-            let mut number_of_ones = 0;
-            for (i, mut word) in ones_bitvector_copy.iter().cloned().enumerate() {
-                while word != 0 {
-                    // Get the bit position of the current one
-                    let idx = (i << WORD_SHIFT) as u64 + word.trailing_zeros() as u64;
-
-                    // write the index
-                    if number_of_ones & INDEX_MASK == 0 {
-                        high_bits_index_ones.push(idx as u64);
-                    }
-
-                    // Clean the one so that we can get to the next one.
-                    word &= word - 1;
-                    number_of_ones += 1;
-                }
-            }
-            (number_of_ones, high_bits_index_ones)
-        });
-
-        let mut high_bits_index_zeros = Vec::with_capacity(bitvector.len() >> INDEX_SHIFT);
-        let mut number_of_zeros = 0;
-        for (i, mut word) in bitvector.iter().cloned().enumerate() {
-            while word != u64::MAX {
-                // Get the bit position of the current one
-                let idx = (i << WORD_SHIFT) as u64 + word.trailing_ones() as u64;
-
-                // write the index
-                if number_of_zeros & INDEX_MASK == 0 {
-                    high_bits_index_zeros.push(idx as u64);
-                }
-
-                // set the zero so that we can get to the next zero.
-                word |= word + 1;
-                number_of_zeros += 1;
-            }
-        }
-
-        let (number_of_ones, high_bits_index_ones) = ones_counter.join().unwrap();
-
-        let bitvector = Arc::try_unwrap(bitvector).unwrap();
+        #[cfg(feature = "std")]
+        let (bitvector, (number_of_ones, high_bits_index_ones), (number_of_zeros, high_bits_index_zeros)) = {
+            let bitvector = Arc::new(bitvector);
+            let ones_bitvector_copy = bitvector.clone();
+            let ones_counter = std::thread::spawn(move || build_index_ones(&ones_bitvector_copy));
+            let zeros = build_index_zeros(&bitvector);
+            let ones = ones_counter.join().unwrap();
+            (Arc::try_unwrap(bitvector).unwrap(), ones, zeros)
+        };
+
+        // without threads the indices are built one after the other
+        #[cfg(not(feature = "std"))]
+        let (number_of_ones, high_bits_index_ones) = build_index_ones(&bitvector);
+        #[cfg(not(feature = "std"))]
+        let (number_of_zeros, high_bits_index_zeros) = build_index_zeros(&bitvector);
 
         SimpleSelect{
             len: (bitvector.len() << WORD_SHIFT) as u64,
@@ -149,3 +147,49 @@ impl SimpleSelect {
         }
     }
 }
+
+/// Return the number of ones in the bitvector and the index with the
+/// position of every `2^INDEX_SHIFT`-th one.
+fn build_index_ones(bitvector: &[u64]) -> (u64, Vec<u64>) {
+    let mut high_bits_index_ones = Vec::with_capacity(bitvector.len() >> INDEX_SHIFT);
+    let mut number_of_ones = 0;
+    for (i, mut word) in bitvector.iter().cloned().enumerate() {
+        while word != 0 {
+            // Get the bit position of the current one
+            let idx = (i << WORD_SHIFT) as u64 + word.trailing_zeros() as u64;
+
+            // write the index
+            if number_of_ones & INDEX_MASK == 0 {
+                high_bits_index_ones.push(idx);
+            }
+
+            // Clean the one so that we can get to the next one.
+            word &= word - 1;
+            number_of_ones += 1;
+        }
+    }
+    (number_of_ones, high_bits_index_ones)
+}
+
+/// Return the number of zeros in the bitvector and the index with the
+/// position of every `2^INDEX_SHIFT`-th zero.
+fn build_index_zeros(bitvector: &[u64]) -> (u64, Vec<u64>) {
+    let mut high_bits_index_zeros = Vec::with_capacity(bitvector.len() >> INDEX_SHIFT);
+    let mut number_of_zeros = 0;
+    for (i, mut word) in bitvector.iter().cloned().enumerate() {
+        while word != u64::MAX {
+            // Get the bit position of the current one
+            let idx = (i << WORD_SHIFT) as u64 + word.trailing_ones() as u64;
+
+            // write the index
+            if number_of_zeros & INDEX_MASK == 0 {
+                high_bits_index_zeros.push(idx);
+            }
+
+            // set the zero so that we can get to the next zero.
+            word |= word + 1;
+            number_of_zeros += 1;
+        }
+    }
+    (number_of_zeros, high_bits_index_zeros)
+}
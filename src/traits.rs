@@ -0,0 +1,297 @@
+//! The traits shared by the sorted sequences and by the bitvectors,
+//! implemented also for the dictionaries of other crates.
+use super::*;
+
+/// A sequence of bits which can be read at random positions.
+pub trait BitVector {
+    /// Return the number of bits.
+    fn len(&self) -> u64;
+
+    /// Return if there are no bits.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the value of the bit of position `index`.
+    fn get(&self, index: u64) -> bool;
+}
+
+/// Bitvectors which can count the bits before a position.
+pub trait Rank: BitVector {
+    /// Return the number of bits set to one in the range [0, `index`).
+    fn rank1(&self, index: u64) -> u64;
+
+    /// Return the number of bits set to zero in the range [0, `index`).
+    fn rank0(&self, index: u64) -> u64 {
+        index.min(self.len()).saturating_sub(self.rank1(index))
+    }
+}
+
+/// Bitvectors which can find the position of the ones.
+pub trait Select: BitVector {
+    /// Return the position of the `index`-th bit set to one.
+    fn select1(&self, index: u64) -> u64;
+}
+
+/// Bitvectors which can find the position of the zeros.
+pub trait SelectZero: BitVector {
+    /// Return the position of the `index`-th bit set to zero.
+    fn select0(&self, index: u64) -> u64;
+}
+
+/// Common interface of the encodings of sorted sequences of integers,
+/// so that the same code can work on any of them and pick the smaller one.
+pub trait SortedSequence {
+    /// The type of the values in the sequence.
+    type Value: UnsignedInteger;
+
+    /// Return the number of values in the sequence.
+    fn len(&self) -> usize;
+
+    /// Return if the sequence has no values.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the value of the chosen index.
+    fn select(&self, index: u64) -> Result<Self::Value, String>;
+
+    /// Decode all the values of the sequence.
+    fn decode(&self) -> Vec<Self::Value>;
+
+    /// Return the smallest value which is greater or equal to `value`, if any.
+    fn next_geq(&self, value: Self::Value) -> Option<Self::Value>;
+
+    /// Return the memory used in bytes.
+    fn size(&self) -> usize;
+}
+
+impl<T: UnsignedInteger> SortedSequence for EliasFano<T> {
+    type Value = T;
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn select(&self, index: u64) -> Result<T, String> {
+        self.select(index)
+    }
+
+    #[inline]
+    fn decode(&self) -> Vec<T> {
+        self.iter().collect()
+    }
+
+    #[inline]
+    fn next_geq(&self, value: T) -> Option<T> {
+        self.next_geq(value)
+    }
+
+    #[inline]
+    fn size(&self) -> usize {
+        self.size()
+    }
+}
+
+impl BitVector for SimpleSelect {
+    #[inline]
+    fn len(&self) -> u64 {
+        self.len()
+    }
+
+    #[inline]
+    fn get(&self, index: u64) -> bool {
+        self.get(index)
+    }
+}
+
+impl Rank for SimpleSelect {
+    #[inline]
+    fn rank1(&self, index: u64) -> u64 {
+        self.rank1(index)
+    }
+
+    #[inline]
+    fn rank0(&self, index: u64) -> u64 {
+        self.rank0(index)
+    }
+}
+
+impl Select for SimpleSelect {
+    #[inline]
+    fn select1(&self, index: u64) -> u64 {
+        self.select1(index)
+    }
+}
+
+impl SelectZero for SimpleSelect {
+    #[inline]
+    fn select0(&self, index: u64) -> u64 {
+        self.select0(index)
+    }
+}
+
+/// Return the value of a position of the bitvector, if it fits in `T`.
+#[inline]
+fn position_to_value<T: UnsignedInteger>(position: u64) -> Option<T> {
+    match position as u128 <= T::MAX.to_u128() {
+        true => Some(T::from_u64(position)),
+        false => None,
+    }
+}
+
+/// An EliasFano is seen as the bitvector of length `universe + 1` with the
+/// bits of the values in the set to one. If there are duplicated values,
+/// `rank1` and `select1` count every copy. The positions are `u64`, so the
+/// values of an `EliasFano<u128>` must fit in a `u64`.
+impl<T: UnsignedInteger> BitVector for EliasFano<T> {
+    #[inline]
+    fn len(&self) -> u64 {
+        self.universe.to_u128().saturating_add(1).min(u64::MAX as u128) as u64
+    }
+
+    #[inline]
+    fn get(&self, index: u64) -> bool {
+        position_to_value(index).is_some_and(|value| self.contains(value))
+    }
+}
+
+impl<T: UnsignedInteger> Rank for EliasFano<T> {
+    #[inline]
+    fn rank1(&self, index: u64) -> u64 {
+        match position_to_value(index) {
+            Some(value) => self.unchecked_rank(value),
+            None => self.current_number_of_elements,
+        }
+    }
+}
+
+impl<T: UnsignedInteger> Select for EliasFano<T> {
+    #[inline]
+    fn select1(&self, index: u64) -> u64 {
+        self.unchecked_select(index).to_u64()
+    }
+}
+
+/// In strict mode there are no duplicated values, so the zeros are found with
+/// a binary search in O(log n). Otherwise the duplicates make the number of
+/// zeros before a value unknown, so the distinct values are scanned in O(n).
+impl<T: UnsignedInteger> SelectZero for EliasFano<T> {
+    fn select0(&self, index: u64) -> u64 {
+        if self.strict {
+            // the number of zeros before the value of index `i` is `x_i - i`,
+            // so find how many values are before the searched zero
+            let (mut low, mut high) = (0, self.current_number_of_elements);
+            while low < high {
+                let middle = low + (high - low) / 2;
+                if self.unchecked_select(middle).to_u64() - middle <= index {
+                    low = middle + 1;
+                } else {
+                    high = middle;
+                }
+            }
+            return index + low;
+        }
+        // count the zeros in the gaps between the distinct values
+        let mut zeros = 0;
+        let mut position = 0;
+        for value in self.iter_uniques() {
+            let value = value.to_u64();
+            let gap = value - position;
+            if index < zeros + gap {
+                break;
+            }
+            zeros += gap;
+            position = value + 1;
+        }
+        position + (index - zeros)
+    }
+}
+
+#[cfg(feature = "fid")]
+impl BitVector for fid::BitVector {
+    #[inline]
+    fn len(&self) -> u64 {
+        fid::FID::len(self)
+    }
+
+    #[inline]
+    fn get(&self, index: u64) -> bool {
+        fid::FID::get(self, index)
+    }
+}
+
+#[cfg(feature = "fid")]
+impl Rank for fid::BitVector {
+    #[inline]
+    fn rank1(&self, index: u64) -> u64 {
+        fid::FID::rank1(self, index)
+    }
+
+    #[inline]
+    fn rank0(&self, index: u64) -> u64 {
+        fid::FID::rank0(self, index)
+    }
+}
+
+#[cfg(feature = "fid")]
+impl Select for fid::BitVector {
+    #[inline]
+    fn select1(&self, index: u64) -> u64 {
+        fid::FID::select1(self, index)
+    }
+}
+
+#[cfg(feature = "fid")]
+impl SelectZero for fid::BitVector {
+    #[inline]
+    fn select0(&self, index: u64) -> u64 {
+        fid::FID::select0(self, index)
+    }
+}
+
+#[cfg(feature = "succinct")]
+impl<R: succinct::BitVec + succinct::BitRankSupport> BitVector for succinct::BinSearchSelect<R> {
+    #[inline]
+    fn len(&self) -> u64 {
+        succinct::BitVec::bit_len(self)
+    }
+
+    #[inline]
+    fn get(&self, index: u64) -> bool {
+        succinct::BitVec::get_bit(self, index)
+    }
+}
+
+/// The ranks of succinct include the bit at the given position, so they
+/// are shifted by one.
+#[cfg(feature = "succinct")]
+impl<R: succinct::BitVec + succinct::BitRankSupport> Rank for succinct::BinSearchSelect<R> {
+    #[inline]
+    fn rank1(&self, index: u64) -> u64 {
+        match index.min(BitVector::len(self)) {
+            0 => 0,
+            index => succinct::BitRankSupport::rank1(self, index - 1),
+        }
+    }
+}
+
+#[cfg(feature = "succinct")]
+impl<R: succinct::BitVec + succinct::BitRankSupport> Select for succinct::BinSearchSelect<R> {
+    #[inline]
+    fn select1(&self, index: u64) -> u64 {
+        succinct::select::Select1Support::select1(self, index)
+            .expect("The index of the one is out of bound.")
+    }
+}
+
+#[cfg(feature = "succinct")]
+impl<R: succinct::BitVec + succinct::BitRankSupport> SelectZero for succinct::BinSearchSelect<R> {
+    #[inline]
+    fn select0(&self, index: u64) -> u64 {
+        succinct::select::Select0Support::select0(self, index)
+            .expect("The index of the zero is out of bound.")
+    }
+}
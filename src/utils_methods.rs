@@ -1,11 +1,13 @@
 use super::*;
 
-impl EliasFano {
+impl<T: UnsignedInteger> EliasFano<T> {
     /// Return the number of **bits** used by the structure
-
+    #[cfg(feature = "std")]
     pub fn debug(&self) {
         println!("------------ELIAS-FANO------------------");
         println!("\tuniverse: {}", self.universe);
+        println!("\tmin_value: {}", self.min_value);
+        println!("\tstrict: {}", self.strict);
         println!("\tnumber_of_elements: {}", self.number_of_elements);
         println!(
             "\tcurrent_number_of_elements: {}",
@@ -21,7 +23,7 @@ impl EliasFano {
             }
             println!("\n--------------high-bits-----------------");
             for i in 0..self.high_bits.len() {
-                print!("{}", self.high_bits.get(i) as u64);
+                print!("{}", self.high_bits.unchecked_get(i) as u64);
             }
             // println!("\n--------------values--------------------");
             // for v in self.iter() {
@@ -0,0 +1,290 @@
+use super::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The invariants checked by `validate`.
+pub enum Invariant {
+    /// The length is the sum of the ones and the zeros and matches the number of words.
+    Length,
+    /// The number of ones in the words matches the stored one.
+    Popcount,
+    /// The bits after the end of the data are zero.
+    Padding,
+    /// The index of the ones samples the position of every `INDEX_SHIFT`-th one.
+    IndexOnes,
+    /// The index of the zeros samples the position of every `INDEX_SHIFT`-th zero.
+    IndexZeros,
+    /// The number of elements matches the ones of the high-bits and the capacity.
+    ElementCount,
+    /// The low-bits have the right width, mask and number of words.
+    LowBits,
+    /// The values are sorted, or strictly increasing in strict mode.
+    Monotonicity,
+    /// The values are in [`min_value`, `universe`].
+    Range,
+    /// The cached `last_*` fields match the last value.
+    LastValue,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// An invariant which does not hold, with the details of why.
+pub struct InvariantViolation {
+    pub invariant: Invariant,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+/// The result of `validate`, with all the invariants which do not hold.
+pub struct ValidationReport {
+    pub violations: Vec<InvariantViolation>,
+}
+
+impl ValidationReport {
+    /// Return if all the invariants hold.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Return if the given invariant does not hold.
+    #[inline]
+    pub fn violates(&self, invariant: Invariant) -> bool {
+        self.violations.iter().any(|violation| violation.invariant == invariant)
+    }
+
+    /// Convert the report into an error with all the violations, if any.
+    pub fn into_result(self) -> Result<(), String> {
+        match self.is_valid() {
+            true => Ok(()),
+            false => Err(self.violations.into_iter()
+                .map(|violation| violation.message)
+                .collect::<Vec<_>>()
+                .join("\n")),
+        }
+    }
+
+    #[inline]
+    fn push(&mut self, invariant: Invariant, message: String) {
+        self.violations.push(InvariantViolation{invariant, message});
+    }
+}
+
+impl SimpleSelect {
+    /// Check all the invariants of the bitvector and its indices.
+    ///
+    /// ```
+    /// # use elias_fano_rust::{SimpleSelect, Invariant};
+    /// let bitmap = SimpleSelect::from_vec(vec![0b1011, 0, u64::MAX]);
+    /// assert!(bitmap.validate().is_valid());
+    /// let corrupted = unsafe {
+    ///     SimpleSelect::from_raw_parts(vec![0b1011, 0, u64::MAX], vec![2], vec![0], 68, 124, 192)
+    /// };
+    /// assert!(corrupted.validate().violates(Invariant::Popcount));
+    /// ```
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+
+        if self.number_of_ones + self.number_of_zeros != self.len {
+            report.push(Invariant::Length, format!(
+                "The SimpleSelect has {} ones and {} zeros but its length is {}.",
+                self.number_of_ones, self.number_of_zeros, self.len
+            ));
+        }
+        let words = (self.len + WORD_MASK) >> WORD_SHIFT;
+        if self.high_bits.len() as u64 != words {
+            report.push(Invariant::Length, format!(
+                "The SimpleSelect has length {} so it needs {} words but it has {}.",
+                self.len, words, self.high_bits.len()
+            ));
+            // the positions cannot be checked without the words
+            return report;
+        }
+
+        let padding = self.len & WORD_MASK;
+        if padding != 0 && self.high_bits[self.high_bits.len() - 1] >> padding != 0 {
+            report.push(Invariant::Padding, format!(
+                "The bits after the length {} of the SimpleSelect are not zero.",
+                self.len
+            ));
+        }
+
+        // compute the samples of the positions of the ones and of the zeros
+        let (mut ones, mut zeros) = (0_u64, 0_u64);
+        let mut index_ones = Vec::with_capacity(self.high_bits_index_ones.len());
+        let mut index_zeros = Vec::with_capacity(self.high_bits_index_zeros.len());
+        for (i, word) in self.high_bits.iter().enumerate() {
+            let bits_in_word = (self.len - ((i as u64) << WORD_SHIFT)).min(WORD_SIZE);
+            for bit in 0..bits_in_word {
+                let position = ((i as u64) << WORD_SHIFT) + bit;
+                if word >> bit & 1 == 1 {
+                    if ones & INDEX_MASK == 0 {
+                        index_ones.push(position);
+                    }
+                    ones += 1;
+                } else {
+                    if zeros & INDEX_MASK == 0 {
+                        index_zeros.push(position);
+                    }
+                    zeros += 1;
+                }
+            }
+        }
+
+        if ones != self.number_of_ones || zeros != self.number_of_zeros {
+            report.push(Invariant::Popcount, format!(
+                concat!(
+                    "The SimpleSelect should have {} ones and {} zeros ",
+                    "but its words have {} ones and {} zeros."
+                ),
+                self.number_of_ones, self.number_of_zeros, ones, zeros
+            ));
+        }
+        if index_ones != self.high_bits_index_ones {
+            report.push(Invariant::IndexOnes, format!(
+                "The index of the ones of the SimpleSelect is {:?} but the ones are at {:?}.",
+                self.high_bits_index_ones, index_ones
+            ));
+        }
+        if index_zeros != self.high_bits_index_zeros {
+            report.push(Invariant::IndexZeros, format!(
+                "The index of the zeros of the SimpleSelect is {:?} but the zeros are at {:?}.",
+                self.high_bits_index_zeros, index_zeros
+            ));
+        }
+
+        report
+    }
+}
+
+impl<T: UnsignedInteger> EliasFano<T> {
+    /// Check all the invariants of the EliasFano, including the ones of
+    /// its high-bits. This is useful after building one by hand or with
+    /// code that trusts its inputs, like `ConcurrentEliasFanoBuilder::build`.
+    ///
+    /// ```
+    /// # use elias_fano_rust::{EliasFano, Invariant};
+    /// let ef = EliasFano::from_vec(&[5_u64, 8, 8, 15, 32]).unwrap();
+    /// assert!(ef.validate().is_valid());
+    /// // the universe is smaller than the last value
+    /// let corrupted = unsafe {
+    ///     EliasFano::from_raw_parts(
+    ///         ef.low_bits().to_vec(), ef.high_bits().clone(), 20,
+    ///         ef.get_min_value(), ef.is_strict(), ef.capacity(), ef.low_bit_count(),
+    ///     )
+    /// };
+    /// assert!(corrupted.validate().violates(Invariant::Range));
+    /// ```
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = self.high_bits.validate();
+        let mut structure_is_valid = report.is_valid();
+
+        if self.high_bits.count_ones() != self.current_number_of_elements
+            || self.last_index != self.current_number_of_elements
+            || self.current_number_of_elements > self.number_of_elements
+        {
+            structure_is_valid = false;
+            report.push(Invariant::ElementCount, format!(
+                concat!(
+                    "The EliasFano has {} elements, {} as last index, {} ones ",
+                    "in the high-bits and a capacity of {} elements."
+                ),
+                self.current_number_of_elements, self.last_index,
+                self.high_bits.count_ones(), self.number_of_elements
+            ));
+        }
+
+        if self.low_bit_count > T::BITS || self.low_bit_mask != T::mask(self.low_bit_count) {
+            structure_is_valid = false;
+            report.push(Invariant::LowBits, format!(
+                "The EliasFano has {} low bits of type of {} bits with the mask {}.",
+                self.low_bit_count, T::BITS, self.low_bit_mask
+            ));
+        } else if self.number_of_elements > 0 {
+            // the low-bits are allocated for the capacity, so that a push cannot write past them,
+            // a crafted capacity saturates so that it is reported instead of overflowing
+            let words = 3 + (self.number_of_elements.saturating_mul(self.low_bit_count) >> WORD_SHIFT);
+            if (self.low_bits.len() as u64) < words {
+                structure_is_valid = false;
+                report.push(Invariant::LowBits, format!(
+                    "The EliasFano has a capacity of {} elements so it needs {} words for the low-bits but it has {}.",
+                    self.number_of_elements, words, self.low_bits.len()
+                ));
+            } else if self.current_number_of_elements <= self.number_of_elements {
+                // the padding is only defined if the number of elements is valid
+                let used_bits = self.current_number_of_elements * self.low_bit_count;
+                let word = (used_bits >> WORD_SHIFT) as usize;
+                let dirty_word = self.low_bits.get(word)
+                    .is_some_and(|bits| bits >> (used_bits & WORD_MASK) != 0);
+                let dirty_tail = self.low_bits.get(word + 1..)
+                    .is_some_and(|words| words.iter().any(|bits| *bits != 0));
+                if dirty_word || dirty_tail {
+                    report.push(Invariant::Padding, format!(
+                        "The low-bits after the {} elements of the EliasFano are not zero.",
+                        self.current_number_of_elements
+                    ));
+                }
+            }
+        }
+
+        if self.min_value > self.universe {
+            report.push(Invariant::Range, format!(
+                "The minimum value {} of the EliasFano is bigger than the universe {}.",
+                self.min_value, self.universe
+            ));
+        }
+
+        // the values can only be decoded if the structure is consistent
+        if !structure_is_valid {
+            return report;
+        }
+
+        let mut previous: Option<u128> = None;
+        for (index, position) in self.high_bits.iter().enumerate() {
+            let index = index as u64;
+            let high = (position - index) as u128;
+            let low = self.read_lowbits(index).to_u128();
+            // decode in u128 so that corrupted values cannot overflow
+            let encoded = match self.low_bit_count {
+                0 => Some(high),
+                bits if (high.leading_zeros() as u64) < bits => None,
+                bits => Some(high << bits | low),
+            };
+            let value = encoded
+                .and_then(|encoded| encoded.checked_add(self.min_value.to_u128()))
+                .and_then(|value| value.checked_add(if self.strict { index as u128 } else { 0 }))
+                .filter(|value| *value <= self.universe.to_u128());
+            let value = match value {
+                Some(value) => value,
+                None => {
+                    report.push(Invariant::Range, format!(
+                        "The value of index {} of the EliasFano is not in [{}, {}].",
+                        index, self.min_value, self.universe
+                    ));
+                    return report;
+                }
+            };
+            if let Some(previous) = previous {
+                if value < previous || (self.strict && value == previous) {
+                    report.push(Invariant::Monotonicity, format!(
+                        "The value of index {} of the EliasFano is {} but the previous one is {}.",
+                        index, value, previous
+                    ));
+                }
+            }
+            previous = Some(value);
+
+            if index + 1 == self.current_number_of_elements
+                && (value != self.last_value.to_u128() || high as u64 != self.last_high_value)
+            {
+                report.push(Invariant::LastValue, format!(
+                    concat!(
+                        "The last value of the EliasFano is {} with high-bits {} ",
+                        "but the cached ones are {} and {}."
+                    ),
+                    value, high, self.last_value, self.last_high_value
+                ));
+            }
+        }
+
+        report
+    }
+}
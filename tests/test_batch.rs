@@ -0,0 +1,111 @@
+use elias_fano_rust::*;
+mod utils;
+use utils::*;
+
+#[test]
+/// Check that the batch queries return the same results of the single ones.
+fn test_batch() {
+    for (size, max) in &[(1, 10), (100, 100), (1_000, 1_500), (10_000, 1_000_000), (10_000, 1 << 50)] {
+        let vector = build_random_sorted_vector(*size, *max);
+        let mut strict_vector = vector.clone();
+        strict_vector.dedup();
+        let structures = vec![
+            EliasFano::from_vec(&vector).unwrap(),
+            EliasFano::from_vec_strict(&strict_vector).unwrap(),
+        ];
+        for ef in structures {
+            // shuffled indices, so that the queries are not sorted
+            let indices = (0..ef.len() as u64).map(|i| (i * 7919) % ef.len() as u64).collect::<Vec<_>>();
+            let mut values = vec![0; indices.len()];
+            ef.select_batch(&indices, &mut values);
+            for (index, value) in indices.iter().zip(values.iter()) {
+                assert_eq!(*value, ef.unchecked_select(*index), "select of {}", index);
+            }
+
+            let last = ef.unchecked_select(ef.len() as u64 - 1);
+            let queries = (0..last + 10).rev().step_by(1 + (last as usize / 5_000)).collect::<Vec<_>>();
+            let mut ranks = vec![0; queries.len()];
+            ef.rank_batch(&queries, &mut ranks);
+            for (value, rank) in queries.iter().zip(ranks.iter()) {
+                assert_eq!(*rank, ef.unchecked_rank(*value), "rank of {}", value);
+            }
+        }
+    }
+}
+
+#[test]
+/// Check the batch queries with a minimum value, a smaller type and no queries.
+fn test_batch_corner_cases() {
+    let ef = EliasFano::from_vec(&[100_u32, 100, 1_000, 70_000, 70_001]).unwrap();
+    let mut values = vec![0; 5];
+    ef.select_batch(&[4, 3, 2, 1, 0], &mut values);
+    assert_eq!(values, vec![70_001, 70_000, 1_000, 100, 100]);
+    let mut ranks = vec![0; 6];
+    ef.rank_batch(&[0, 100, 101, 70_001, 70_002, u32::MAX], &mut ranks);
+    assert_eq!(ranks, vec![0, 0, 2, 4, 5, 5]);
+
+    ef.select_batch(&[], &mut []);
+    ef.rank_batch(&[], &mut []);
+    let empty = EliasFano::<u64>::new(1_000, 10).unwrap();
+    let mut ranks = vec![1; 2];
+    empty.rank_batch(&[0, 500], &mut ranks);
+    assert_eq!(ranks, vec![0, 0]);
+}
+
+#[test]
+#[should_panic(expected = "must have the same length")]
+/// Check that the result must have a slot for each query.
+fn test_batch_length_mismatch() {
+    let ef = EliasFano::from_vec(&[5_u64, 8, 8, 15, 32]).unwrap();
+    let mut values = vec![0; 2];
+    ef.select_batch(&[0, 1, 2], &mut values);
+}
+
+#[test]
+/// Check that the sorted batches return the same results of the single queries,
+/// with both dense queries, which scan the high-bits, and sparse ones, which skip.
+fn test_sorted_batch() {
+    for (size, max) in &[(1, 10), (100, 100), (1_000, 1_500), (10_000, 1_000_000), (10_000, 1 << 50)] {
+        let vector = build_random_sorted_vector(*size, *max);
+        let mut strict_vector = vector.clone();
+        strict_vector.dedup();
+        let structures = vec![
+            EliasFano::from_vec(&vector).unwrap(),
+            EliasFano::from_vec_strict(&strict_vector).unwrap(),
+        ];
+        for ef in structures {
+            let last = ef.unchecked_select(ef.len() as u64 - 1);
+            for step in &[1 + last as usize / 100_000, 1 + last as usize / 5_000, 1 + last as usize / 10] {
+                let queries = (0..last + 10).step_by(*step).collect::<Vec<_>>();
+                let mut ranks = vec![0; queries.len()];
+                let mut contains = vec![false; queries.len()];
+                ef.rank_sorted_batch(&queries, &mut ranks);
+                ef.contains_sorted_batch(&queries, &mut contains);
+                for ((value, rank), found) in queries.iter().zip(ranks.iter()).zip(contains.iter()) {
+                    assert_eq!(*rank, ef.unchecked_rank(*value), "rank of {}", value);
+                    assert_eq!(*found, ef.contains(*value), "contains of {}", value);
+                }
+            }
+
+            // the elements themselves, with their duplicates
+            let elements = ef.iter().collect::<Vec<_>>();
+            let mut contains = vec![false; elements.len()];
+            ef.contains_sorted_batch(&elements, &mut contains);
+            assert!(contains.iter().all(|found| *found));
+        }
+    }
+}
+
+#[test]
+/// Check that the sorted batches are right also on unsorted values and on
+/// a set with a minimum value.
+fn test_sorted_batch_unsorted() {
+    let ef = EliasFano::from_vec(&[100_u32, 100, 1_000, 70_000, 70_001]).unwrap();
+    let queries = [70_001, 0, 100, 70_002, 1_000, 999, u32::MAX, 101];
+    let mut ranks = vec![0; queries.len()];
+    let mut contains = vec![false; queries.len()];
+    ef.rank_sorted_batch(&queries, &mut ranks);
+    ef.contains_sorted_batch(&queries, &mut contains);
+    assert_eq!(ranks, vec![4, 0, 0, 5, 2, 2, 5, 2]);
+    assert_eq!(contains, vec![true, false, true, false, true, false, false, false]);
+}
@@ -0,0 +1,108 @@
+use elias_fano_rust::*;
+use rand::rngs::SmallRng;
+use rand::RngCore;
+use rand::SeedableRng;
+use std::collections::BTreeSet;
+mod utils;
+use utils::*;
+
+/// Build a set with a sparse chunk, a dense chunk, a full chunk and
+/// some values scattered on the whole u64 range.
+fn build_skewed_set(rng: &mut SmallRng) -> BTreeSet<u64> {
+    let mut values = BTreeSet::new();
+    for _ in 0..1_000 {
+        values.insert(rng.next_u64());
+    }
+    for _ in 0..100 {
+        values.insert(5 << 16 | (rng.next_u64() & 0xffff));
+    }
+    for _ in 0..30_000 {
+        values.insert(7 << 16 | (rng.next_u64() & 0xffff));
+    }
+    for _ in 0..3_000 {
+        values.insert(8 << 16 | (rng.next_u64() & 0x3fff));
+    }
+    values.extend(9 << 16..10 << 16);
+    values.insert(u64::MAX);
+    values.insert(0);
+    values
+}
+
+/// Check all the methods of the set against the values.
+fn chunked_test_suite(set: &ChunkedSet, values: &[u64]) {
+    assert_eq!(set.len(), values.len() as u64);
+    assert_eq!(set.iter().collect::<Vec<_>>(), values);
+    assert_eq!(set.select(values.len() as u64), None);
+    for (i, value) in values.iter().enumerate() {
+        assert_eq!(set.select(i as u64), Some(*value));
+        assert_eq!(set.rank(*value), i as u64);
+        assert!(set.contains(*value));
+        if *value > 0 && values.binary_search(&(value - 1)).is_err() {
+            assert!(!set.contains(value - 1));
+            assert_eq!(set.rank(value - 1), i as u64);
+        }
+    }
+}
+
+#[test]
+/// Check that the set works with all the kinds of containers.
+fn test_chunked_set() {
+    let mut rng = SmallRng::from_seed(SEED);
+    let values = build_skewed_set(&mut rng).into_iter().collect::<Vec<_>>();
+    let set = ChunkedSet::from_vec(&values).unwrap();
+    chunked_test_suite(&set, &values);
+
+    let mut kinds = [false; 3];
+    for (_, container) in set.chunks() {
+        match container {
+            ChunkContainer::Array(_) => kinds[0] = true,
+            ChunkContainer::Bitmap(_) => kinds[1] = true,
+            ChunkContainer::EliasFano(_) => kinds[2] = true,
+        }
+    }
+    assert_eq!(kinds, [true; 3]);
+
+    // the builder accepts unsorted and duplicated values
+    let mut builder = ChunkedSetBuilder::new();
+    for value in values.iter().rev().chain(values.iter()) {
+        builder.insert(*value);
+    }
+    assert_eq!(builder.build().unwrap(), set);
+
+    assert!(ChunkedSet::from_vec(&[1, 1]).is_err());
+    assert!(ChunkedSet::from_vec(&[2, 1]).is_err());
+    let empty = ChunkedSet::from_vec(&[]).unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(empty.rank(10), 0);
+}
+
+#[test]
+/// Check the set operations against the ones of BTreeSet.
+fn test_chunked_set_operations() {
+    let mut rng = SmallRng::from_seed(SEED);
+    let a = build_skewed_set(&mut rng);
+    let b = build_skewed_set(&mut rng);
+    let set_a = ChunkedSet::from_iter(a.iter().cloned()).unwrap();
+    let set_b = ChunkedSet::from_iter(b.iter().cloned()).unwrap();
+
+    let truth = a.intersection(&b).cloned().collect::<Vec<_>>();
+    chunked_test_suite(&set_a.intersection(&set_b), &truth);
+    let truth = a.union(&b).cloned().collect::<Vec<_>>();
+    chunked_test_suite(&set_a.union(&set_b), &truth);
+    let truth = a.difference(&b).cloned().collect::<Vec<_>>();
+    chunked_test_suite(&set_a.difference(&set_b), &truth);
+    let truth = b.difference(&a).cloned().collect::<Vec<_>>();
+    chunked_test_suite(&set_b.difference(&set_a), &truth);
+
+    assert!(set_a.difference(&set_a).is_empty());
+    assert_eq!(set_a.union(&set_a).iter().collect::<Vec<_>>(), set_a.iter().collect::<Vec<_>>());
+}
+
+#[test]
+/// Check that the invalid values of a container are reported.
+fn test_chunk_container_errors() {
+    assert!(ChunkContainer::from_sorted(&[]).is_err());
+    assert!(ChunkContainer::from_sorted(&[5, 3]).is_err());
+    assert!(ChunkContainer::from_sorted(&[3, 3]).is_err());
+    assert_eq!(ChunkContainer::from_sorted(&[3, 5]).unwrap().len(), 2);
+}
@@ -0,0 +1,136 @@
+use elias_fano_rust::codes::*;
+use rand::rngs::SmallRng;
+use rand::RngCore;
+use rand::SeedableRng;
+mod utils;
+use utils::*;
+
+/// Return all the small values, the values around the powers of two
+/// and some random values of every size.
+fn build_test_values() -> Vec<u64> {
+    let mut rng: SmallRng = SmallRng::from_seed(SEED);
+    let mut values = (0..2_000).collect::<Vec<u64>>();
+    for bits in 1..64 {
+        let power = 1_u64 << bits;
+        values.extend(&[power - 1, power, power + 1]);
+        values.extend((0..10).map(|_| rng.next_u64() >> (64 - bits)));
+    }
+    values.push(u64::MAX - 1);
+    values.push(u64::MAX);
+    values
+}
+
+/// Write all the values with the given code, check that the lengths are the
+/// predicted ones and that they are read back both sequentially and after
+/// interleaving them with other values.
+fn test_code(
+    name: &str,
+    write: impl Fn(&mut BitWriter, u64),
+    read: impl Fn(&mut BitReader) -> Result<u64, String>,
+    len: impl Fn(u64) -> u64,
+    max: u64,
+) {
+    let values = build_test_values().into_iter().filter(|value| *value <= max).collect::<Vec<_>>();
+    let mut writer = BitWriter::new();
+    for value in &values {
+        let start = writer.len();
+        write(&mut writer, *value);
+        assert_eq!(writer.len() - start, len(*value), "length of {} with {}", value, name);
+        writer.write_bits(0b101, 3);
+    }
+
+    let mut reader = writer.reader();
+    for value in &values {
+        assert_eq!(read(&mut reader).unwrap(), *value, "value with {}", name);
+        assert_eq!(reader.read_bits(3).unwrap(), 0b101, "separator after {} with {}", value, name);
+    }
+    assert_eq!(reader.remaining(), 0);
+    assert!(read(&mut reader).is_err());
+
+    // a truncated stream must be an error, not a panic
+    let (data, bits) = writer.into_inner();
+    let mut reader = BitReader::new(&data, bits - 4);
+    while reader.remaining() > 0 {
+        if read(&mut reader).is_err() {
+            break;
+        }
+        if reader.read_bits(3).is_err() {
+            break;
+        }
+    }
+}
+
+#[test]
+/// Check the round-trip of all the codes.
+fn test_codes() {
+    test_code("unary", |w, v| w.write_unary(v), |r| r.read_unary(), unary_len, 10_000);
+    test_code("gamma", |w, v| w.write_gamma(v), |r| r.read_gamma(), gamma_len, u64::MAX);
+    test_code("delta", |w, v| w.write_delta(v), |r| r.read_delta(), delta_len, u64::MAX);
+    test_code("vbyte", |w, v| w.write_vbyte(v), |r| r.read_vbyte(), vbyte_len, u64::MAX);
+
+    for k in 0..=64 {
+        test_code(
+            "rice",
+            |w, v| w.write_rice(v, k).unwrap(),
+            |r| r.read_rice(k),
+            |v| rice_len(v, k),
+            if k < 50 { (10_000 << k) - 1 } else { u64::MAX },
+        );
+    }
+
+    for b in &[1, 2, 3, 5, 7, 8, 10, 100, 1_000, 1 << 20, (1 << 40) + 3, u64::MAX] {
+        test_code(
+            "golomb",
+            |w, v| w.write_golomb(v, *b).unwrap(),
+            |r| r.read_golomb(*b),
+            |v| golomb_len(v, *b),
+            b.saturating_mul(10_000),
+        );
+    }
+
+    for k in 1..64 {
+        test_code(
+            "zeta",
+            |w, v| w.write_zeta(v, k).unwrap(),
+            |r| r.read_zeta(k),
+            |v| zeta_len(v, k),
+            u64::MAX,
+        );
+    }
+}
+
+#[test]
+/// Check the relations between the codes and the invalid parameters.
+fn test_codes_properties() {
+    for value in build_test_values() {
+        assert_eq!(zeta_len(value, 1), gamma_len(value));
+        if value < 1 << 40 {
+            assert_eq!(golomb_len(value, 1 << 5), rice_len(value, 5));
+        }
+    }
+
+    let mut writer = BitWriter::new();
+    assert!(writer.write_golomb(1, 0).is_err());
+    assert!(writer.write_zeta(1, 0).is_err());
+    assert!(writer.write_zeta(1, 64).is_err());
+    assert!(writer.write_rice(5, 70).is_err());
+    assert!(writer.reader().read_rice(65).is_err());
+    assert!(writer.is_empty());
+
+    // 11 bytes with the continuation bit set are not a valid VByte
+    for _ in 0..11 {
+        writer.write_bits(0xff, 8);
+    }
+    assert!(writer.reader().read_vbyte().is_err());
+
+    // a unary code without its terminating one
+    let mut writer = BitWriter::new();
+    writer.write_bits(0, 64);
+    writer.write_bits(0, 10);
+    assert!(writer.reader().read_unary().is_err());
+
+    let mut reader = writer.reader();
+    assert!(reader.set_position(75).is_err());
+    reader.set_position(74).unwrap();
+    assert_eq!(reader.read_bits(0).unwrap(), 0);
+}
@@ -0,0 +1,73 @@
+use elias_fano_rust::*;
+use rand::rngs::SmallRng;
+use rand::RngCore;
+use rand::SeedableRng;
+use rayon::prelude::*;
+mod utils;
+use utils::*;
+
+#[test]
+/// Check the compact vectors against a vector for all the widths.
+fn test_compact_vec() {
+    let mut rng: SmallRng = SmallRng::from_seed(SEED);
+    for width in 0..=64 {
+        let mask = if width == 64 { u64::MAX } else { (1 << width) - 1 };
+        let size = 1 + (rng.next_u64() % 1_000) as usize;
+        let mut truth = (0..size).map(|_| rng.next_u64() & mask).collect::<Vec<_>>();
+
+        let mut vector = CompactVec::new(width, 0).unwrap();
+        for value in &truth {
+            vector.push(*value).unwrap();
+        }
+        assert_eq!(vector.len(), size);
+        assert_eq!(vector.width(), width);
+        assert_eq!(vector.iter().collect::<Vec<_>>(), truth);
+        assert_eq!(vector.par_iter().collect::<Vec<_>>(), truth);
+
+        // overwrite the values to check that the old bits are cleared
+        for _ in 0..size {
+            let index = (rng.next_u64() % size as u64) as usize;
+            let value = rng.next_u64() & mask;
+            vector.set(index, value).unwrap();
+            truth[index] = value;
+        }
+        for (i, value) in truth.iter().enumerate() {
+            assert_eq!(vector.get(i), Some(*value));
+        }
+        assert_eq!(vector.get(size), None);
+        assert!(vector.set(size, 0).is_err());
+        if width < 64 {
+            assert!(vector.set(0, mask + 1).is_err());
+            assert!(vector.push(mask + 1).is_err());
+        }
+
+        let atomic = AtomicCompactVec::new(width, size).unwrap();
+        truth.par_iter().enumerate().for_each(|(i, value)| atomic.set(i, *value).unwrap());
+        truth.par_iter().enumerate().for_each(|(i, value)| atomic.set(i, *value).unwrap());
+        assert_eq!(atomic.get(size / 2), Some(truth[size / 2]));
+        assert_eq!(atomic.into_compact_vec(), vector);
+
+        let mut buffer = Vec::new();
+        vector.serialize(&mut buffer).unwrap();
+        assert_eq!(CompactVec::deserialize(&mut buffer.as_slice()).unwrap(), vector);
+    }
+
+    let vector = CompactVec::from_vec(&[3, 0, 7, 1]).unwrap();
+    assert_eq!(vector.width(), 3);
+    assert!(CompactVec::new(65, 10).is_err());
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+/// The unchecked accessors panic past the allocated values, also with the `unsafe` feature.
+fn test_compact_vec_unchecked_get_out_of_bounds() {
+    let vector = CompactVec::new(5, 3).unwrap();
+    vector.unchecked_get(1 << 40);
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn test_compact_vec_unchecked_set_out_of_bounds() {
+    let mut vector = CompactVec::new(5, 3).unwrap();
+    vector.unchecked_set(1 << 40, 1);
+}
@@ -74,10 +74,10 @@ pub fn test_concurrent_builder() {
 
     // the hashses should be equal!!
     assert_eq!(seq_hash, concurrent_hash);
-    assert_eq!(ef.high_bits.high_bits, seq.high_bits.high_bits);
+    assert_eq!(ef.high_bits_words(), seq.high_bits_words());
 
-    assert_eq!(ef.high_bits.high_bits_index_ones, seq.high_bits.high_bits_index_ones);
-    assert_eq!(ef.high_bits.high_bits_index_zeros, seq.high_bits.high_bits_index_zeros);
+    assert_eq!(ef.high_bits().high_bits_index_ones(), seq.high_bits().high_bits_index_ones());
+    assert_eq!(ef.high_bits().high_bits_index_zeros(), seq.high_bits().high_bits_index_zeros());
 
 
     for i in 0..SIZE {
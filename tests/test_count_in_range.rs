@@ -0,0 +1,71 @@
+use elias_fano_rust::*;
+mod utils;
+use utils::*;
+
+#[test]
+/// Check that count_in_range matches the difference of the ranks, for ranges
+/// inside a single bucket and across many ones.
+fn test_count_in_range() {
+    for (size, max) in &[(1, 10), (100, 100), (1_000, 1_500), (10_000, 1_000_000), (10_000, 1 << 50)] {
+        let vector = build_random_sorted_vector(*size, *max);
+        let mut strict_vector = vector.clone();
+        strict_vector.dedup();
+        let structures = vec![
+            EliasFano::from_vec(&vector).unwrap(),
+            EliasFano::from_vec_strict(&strict_vector).unwrap(),
+        ];
+        for ef in structures {
+            let values = if ef.is_strict() { &strict_vector } else { &vector };
+            let last = *values.last().unwrap();
+            let step = 1 + last / 2_000;
+            for start in (0..last + 10).step_by(step as usize) {
+                for width in &[0, 1, 1 << ef.low_bit_count(), step, last / 3] {
+                    let end = start.saturating_add(*width);
+                    let truth = (values.partition_point(|value| *value < end)
+                        - values.partition_point(|value| *value < start)) as u64;
+                    assert_eq!(ef.count_in_range(start..end), truth, "count in {}..{}", start, end);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+/// Check count_in_range with a minimum value and degenerate ranges.
+fn test_count_in_range_corner_cases() {
+    let ef = EliasFano::from_vec(&[100_u32, 100, 1_000, 70_000, 70_001]).unwrap();
+    assert_eq!(ef.count_in_range(0..100), 0);
+    assert_eq!(ef.count_in_range(0..101), 2);
+    assert_eq!(ef.count_in_range(100..70_001), 4);
+    assert_eq!(ef.count_in_range(70_001..u32::MAX), 1);
+    assert_eq!(ef.count_in_range(1_000..1_000), 0);
+    let (start, end) = (2_000, 1_000);
+    assert_eq!(ef.count_in_range(start..end), 0);
+    assert_eq!(EliasFano::<u64>::new(1_000, 10).unwrap().count_in_range(0..1_000), 0);
+}
+
+#[test]
+/// Check that count_ones_in_range matches the bits, for short and long ranges.
+fn test_count_ones_in_range() {
+    let vector = build_random_sorted_vector(20_000, 100_000);
+    let mut bitmap = SimpleSelect::with_capacity(100_000);
+    let mut bits = vec![false; 100_000];
+    for value in &vector {
+        bits[*value as usize] = true;
+    }
+    for bit in &bits {
+        bitmap.push(*bit);
+    }
+    let mut prefix = vec![0_u64];
+    for bit in &bits {
+        prefix.push(prefix.last().unwrap() + *bit as u64);
+    }
+    for start in (0..100_010_u64).step_by(37) {
+        for width in &[0, 1, 63, 64, 65, 1_000, 1_024, 1_100, 50_000] {
+            let end = start + width;
+            let truth = prefix[end.min(100_000) as usize] - prefix[start.min(100_000) as usize];
+            assert_eq!(bitmap.count_ones_in_range(start..end), truth, "count in {}..{}", start, end);
+            assert_eq!(bitmap.count_ones_in_range(start..end), bitmap.iter_in_range(start..end).count() as u64);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use elias_fano_rust::*;
+use rand::rngs::SmallRng;
+use rand::RngCore;
+use rand::SeedableRng;
+use std::sync::{Arc, Mutex};
+mod utils;
+use utils::*;
+
+/// Build a vector of random values in **random order**.
+pub fn build_random_vector(size: usize, max: u64) -> Vec<u64> {
+    let mut rng: SmallRng = SmallRng::from_seed(SEED);
+    (0..size).map(|_| rng.next_u64() % max).collect()
+}
+
+#[test]
+/// Check that the serialization round-trips.
+fn test_serialize() {
+    for (size, max) in &[(0, 1), (1, 1), (1_000, 1_000), (1_000, 1_000_000), (10_000, u64::MAX)] {
+        let mut vector = build_random_vector(*size, *max);
+        vector.sort();
+        let ef = EliasFano::from_vec(&vector).unwrap();
+
+        let mut buffer = Vec::new();
+        ef.serialize(&mut buffer).unwrap();
+        let loaded = EliasFano::deserialize(&mut buffer.as_slice()).unwrap();
+
+        assert_eq!(ef, loaded);
+        assert_eq!(vector, loaded.iter().collect::<Vec<_>>());
+    }
+
+    assert!(EliasFano::<u64>::deserialize(&mut [0_u8; 64].as_ref()).is_err());
+}
+
+#[test]
+/// Check that the external builder, both in memory and on file, produces
+/// the same EliasFano of the sequential builder.
+fn test_external_builder() {
+    let temp_dir = std::env::temp_dir();
+    for (size, max, buffer_capacity) in &[
+        (0, 1, 10),
+        (1, 100, 10),
+        (1_000, 1_000, 10_000),
+        (10_000, 1_000_000, 1_000),
+        (10_000, 1_000_000, 1),
+        (10_000, u64::MAX, 333),
+    ] {
+        let vector = build_random_vector(*size, *max);
+        let mut sorted = vector.clone();
+        sorted.sort();
+        let truth = EliasFano::from_vec_auto_min(&sorted).unwrap();
+
+        let mut builder = ExternalEliasFanoBuilder::new(&temp_dir, *buffer_capacity).unwrap();
+        builder.extend(vector.iter().cloned()).unwrap();
+        assert_eq!(builder.len(), *size as u64);
+        assert_eq!(builder.build().unwrap(), truth);
+
+        let mut builder = ExternalEliasFanoBuilder::new(&temp_dir, *buffer_capacity).unwrap();
+        builder.extend(vector.iter().cloned()).unwrap();
+        let mut buffer = Vec::new();
+        builder.build_into(&mut buffer).unwrap();
+        let loaded = EliasFano::deserialize(&mut buffer.as_slice()).unwrap();
+        assert_eq!(loaded, truth);
+        assert_eq!(sorted, loaded.iter().collect::<Vec<_>>());
+    }
+}
+
+#[test]
+/// Check that the values can be read from a binary file and that the progress
+/// is reported.
+fn test_external_builder_from_file() {
+    let temp_dir = std::env::temp_dir();
+    let vector = build_random_vector(5_000, 1 << 40);
+    let mut sorted = vector.clone();
+    sorted.sort();
+
+    let bytes = vector.iter().flat_map(|x| x.to_le_bytes().to_vec()).collect::<Vec<u8>>();
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let events_copy = events.clone();
+    let mut builder = ExternalEliasFanoBuilder::new(&temp_dir, 1_000).unwrap()
+        .with_progress(move |event| events_copy.lock().unwrap().push(event));
+    builder.extend_from_reader(bytes.as_slice()).unwrap();
+
+    let path = temp_dir.join(format!("elias_fano_test_{}.ef", std::process::id()));
+    builder.build_to_file(&path).unwrap();
+    let loaded = EliasFano::deserialize(&mut std::fs::File::open(&path).unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(sorted, loaded.iter().collect::<Vec<_>>());
+
+    let events = events.lock().unwrap();
+    assert_eq!(
+        events.iter().filter(|event| matches!(event, ExternalBuilderProgress::RunSpilled{..})).count(),
+        5
+    );
+    assert_eq!(
+        events.last(),
+        Some(&ExternalBuilderProgress::Merged{merged: 5_000, total: 5_000})
+    );
+
+    // a truncated value must be reported
+    let mut builder = ExternalEliasFanoBuilder::new(&temp_dir, 1_000).unwrap();
+    assert!(builder.extend_from_reader(&bytes[..13]).is_err());
+}
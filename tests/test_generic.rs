@@ -0,0 +1,87 @@
+use elias_fano_rust::*;
+use rand::rngs::SmallRng;
+use rand::RngCore;
+use rand::SeedableRng;
+use rayon::prelude::*;
+mod utils;
+use utils::*;
+
+/// Build a sorted vector of random values of the given type.
+fn build_random_sorted_vector<T: UnsignedInteger>(size: usize, max: u128) -> Vec<T> {
+    let mut rng: SmallRng = SmallRng::from_seed(SEED);
+    let mut vector = (0..size).map(|_| {
+        let value = ((rng.next_u64() as u128) << 64) | rng.next_u64() as u128;
+        T::from_u128(value % max)
+    }).collect::<Vec<T>>();
+    vector.sort();
+    vector
+}
+
+/// Check all the methods of elias fano on the given type.
+fn generic_test_suite<T: UnsignedInteger>(size: usize, max: u128) {
+    let vector = build_random_sorted_vector::<T>(size, max);
+    let ef = EliasFano::<T>::from_vec(&vector).unwrap();
+
+    assert_eq!(ef.len(), vector.len());
+    assert_eq!(ef.iter().collect::<Vec<T>>(), vector);
+    assert_eq!(ef.par_iter().collect::<Vec<T>>(), vector);
+
+    vector.iter().enumerate().for_each(|(i, v)| {
+        assert_eq!(*v, ef.select(i as u64).unwrap());
+        assert!(ef.contains(*v));
+        assert_eq!(ef.unchecked_select(ef.unchecked_rank(*v)), *v);
+    });
+
+    if let (Some(first), Some(last)) = (vector.first(), vector.last()) {
+        let truth = vector.iter().cloned()
+            .filter(|x| *first < *x && *x < *last)
+            .collect::<Vec<T>>();
+        assert_eq!(ef.iter_in_range(*first + T::ONE..*last).collect::<Vec<T>>(), truth);
+    }
+
+    let builder = ConcurrentEliasFanoBuilder::<T>::new(
+        vector.len() as u64, *vector.last().unwrap_or(&T::ZERO)
+    ).unwrap();
+    vector.par_iter().enumerate().for_each(|(i, v)| builder.set(i as u64, *v));
+    assert_eq!(
+        builder.build().unwrap(),
+        EliasFano::<T>::from_iter(vector.iter().cloned(), *vector.last().unwrap_or(&T::ZERO), vector.len()).unwrap()
+    );
+
+    let mut buffer = Vec::new();
+    ef.serialize(&mut buffer).unwrap();
+    assert_eq!(EliasFano::<T>::deserialize(&mut buffer.as_slice()).unwrap(), ef);
+}
+
+#[test]
+/// Check that all the supported types work.
+fn test_generic_types() {
+    for size in &[1, 10, 1_000, 10_000] {
+        generic_test_suite::<u8>(*size, 1 << 8);
+        generic_test_suite::<u16>(*size, 1 << 16);
+        generic_test_suite::<u32>(*size, 1 << 32);
+        generic_test_suite::<u64>(*size, 1 << 64);
+        generic_test_suite::<usize>(*size, 1 << 40);
+        generic_test_suite::<u128>(*size, 1 << 100);
+        generic_test_suite::<u128>(*size, u128::MAX);
+    }
+}
+
+#[test]
+/// Check that u128 supports more than 64 low bits.
+fn test_u128_wide_low_bits() {
+    let vector: Vec<u128> = vec![3, 1 << 70, (1 << 90) + 5, u128::MAX - 1];
+    let ef = EliasFano::from_vec(&vector).unwrap();
+    assert!(ef.low_bit_count() > 64);
+    assert_eq!(ef.iter().collect::<Vec<u128>>(), vector);
+    for (i, v) in vector.iter().enumerate() {
+        assert_eq!(ef.unchecked_select(i as u64), *v);
+        assert_eq!(ef.rank(*v), Some(i as u64));
+    }
+    assert!(!ef.contains(4));
+
+    // loading a different type must fail
+    let mut buffer = Vec::new();
+    ef.serialize(&mut buffer).unwrap();
+    assert!(EliasFano::<u64>::deserialize(&mut buffer.as_slice()).is_err());
+}
@@ -0,0 +1,61 @@
+use elias_fano_rust::*;
+use rand::rngs::SmallRng;
+use rand::RngCore;
+use rand::SeedableRng;
+use rayon::prelude::*;
+mod utils;
+use utils::*;
+
+/// Build a sorted vector of random edges without duplicates.
+fn build_random_edges(number_of_edges: usize, number_of_nodes: u64) -> Vec<(u64, u64)> {
+    let mut rng: SmallRng = SmallRng::from_seed(SEED);
+    let mut edges = (0..number_of_edges)
+        .map(|_| (rng.next_u64() % number_of_nodes, rng.next_u64() % number_of_nodes))
+        .collect::<Vec<_>>();
+    edges.sort();
+    edges.dedup();
+    edges
+}
+
+#[test]
+/// Check the graph against the adjacency lists computed from the edges.
+fn test_graph() {
+    for (number_of_edges, number_of_nodes) in &[(0, 1), (10, 3), (1_000, 100), (10_000, 1_000_000)] {
+        let edges = build_random_edges(*number_of_edges, *number_of_nodes);
+        let graph = EliasFanoGraph::from_vec(&edges, *number_of_nodes).unwrap();
+
+        assert_eq!(graph.get_number_of_nodes(), *number_of_nodes);
+        assert_eq!(graph.get_number_of_edges(), edges.len() as u64);
+        assert_eq!(graph.iter_edges().collect::<Vec<_>>(), edges);
+        assert_eq!(graph.edges().collect::<Vec<_>>(), edges);
+
+        for src in edges.iter().map(|(src, _)| *src).chain(0..10.min(*number_of_nodes)) {
+            let truth = edges.iter()
+                .filter(|(edge_src, _)| *edge_src == src)
+                .map(|(_, dst)| *dst)
+                .collect::<Vec<_>>();
+            assert_eq!(graph.neighbours(src).collect::<Vec<_>>(), truth);
+            assert_eq!(graph.degree(src), truth.len() as u64);
+        }
+
+        for (src, dst) in &edges {
+            assert!(graph.has_edge(*src, *dst));
+            assert_eq!(graph.has_edge(*src, (*dst + 1) % number_of_nodes), edges.binary_search(&(*src, (*dst + 1) % number_of_nodes)).is_ok());
+        }
+        assert!(!graph.has_edge(*number_of_nodes, 0));
+        assert_eq!(graph.degree(*number_of_nodes), 0);
+        assert_eq!(graph.neighbours(*number_of_nodes).count(), 0);
+
+        let mut buffer = Vec::new();
+        graph.serialize(&mut buffer).unwrap();
+        assert_eq!(EliasFanoGraph::deserialize(&mut buffer.as_slice()).unwrap(), graph);
+    }
+}
+
+#[test]
+/// Check that the invalid edges are reported.
+fn test_graph_errors() {
+    assert!(EliasFanoGraph::from_vec(&[(0, 1), (1, 5)], 5).is_err());
+    assert!(EliasFanoGraph::from_vec(&[(1, 1), (0, 1)], 5).is_err());
+    assert!(EliasFanoGraph::from_vec(&[], 1 << 33).is_err());
+}
@@ -0,0 +1,73 @@
+use elias_fano_rust::*;
+use rand::rngs::SmallRng;
+use rand::RngCore;
+use rand::SeedableRng;
+mod utils;
+use utils::*;
+
+/// Build a set of values in [0, `universe`] where each value is present
+/// with the given probability.
+fn build_random_set(universe: u64, density: f64) -> Vec<u64> {
+    let mut rng: SmallRng = SmallRng::from_seed(SEED);
+    (0..=universe)
+        .filter(|_| (rng.next_u64() as f64 / u64::MAX as f64) < density)
+        .collect()
+}
+
+/// Check all the methods of the set against the values.
+fn hybrid_test_suite(set: &HybridSet, values: &[u64], universe: u64) {
+    assert_eq!(set.len(), values.len() as u64);
+    assert_eq!(set.iter().collect::<Vec<_>>(), values);
+    for (i, value) in values.iter().enumerate() {
+        assert_eq!(set.select(i as u64), Some(*value));
+    }
+    assert_eq!(set.select(values.len() as u64), None);
+    for value in 0..universe + 10 {
+        let rank = values.partition_point(|x| *x < value) as u64;
+        assert_eq!(set.rank(value), rank, "rank of {} with {:?}", value, set.kind());
+        assert_eq!(set.contains(value), values.binary_search(&value).is_ok());
+    }
+}
+
+#[test]
+/// Check that all the representations work and that the smallest is chosen.
+fn test_hybrid_set() {
+    let universe = 5_000;
+    for density in &[0.0, 0.001, 0.1, 0.5, 0.9, 0.999, 1.0] {
+        let values = build_random_set(universe, *density);
+        let estimates = HybridSet::estimate_sizes(universe, values.len());
+        let mut sizes = Vec::new();
+
+        for kind in &[HybridSetKind::Sparse, HybridSetKind::Dense, HybridSetKind::Complement] {
+            let set = HybridSet::from_iter_with_kind(values.iter().cloned(), universe, values.len(), *kind).unwrap();
+            assert_eq!(set.kind(), *kind);
+            hybrid_test_suite(&set, &values, universe);
+            sizes.push(set.size());
+        }
+
+        let set = HybridSet::from_iter(values.iter().cloned(), universe, values.len()).unwrap();
+        let (best, _) = estimates.iter().min_by_key(|(_, size)| *size).unwrap();
+        assert_eq!(set.kind(), *best);
+        assert_eq!(set.size(), *sizes.iter().min().unwrap(), "size of {:?}", set.kind());
+        hybrid_test_suite(&set, &values, universe);
+    }
+
+    assert_eq!(HybridSet::choose_kind(1 << 40, 10), HybridSetKind::Sparse);
+    assert_eq!(HybridSet::choose_kind((1 << 40) - 1, (1 << 40) - 10), HybridSetKind::Complement);
+    assert_eq!(HybridSet::estimate_sizes(u64::MAX, 10).len(), 1);
+}
+
+#[test]
+/// Check that the invalid inputs are reported.
+fn test_hybrid_set_errors() {
+    for kind in &[HybridSetKind::Sparse, HybridSetKind::Dense, HybridSetKind::Complement] {
+        assert!(HybridSet::from_iter_with_kind([1, 1].iter().cloned(), 10, 2, *kind).is_err());
+        assert!(HybridSet::from_iter_with_kind([1, 11].iter().cloned(), 10, 2, *kind).is_err());
+        assert!(HybridSet::from_iter_with_kind([1, 2].iter().cloned(), 10, 3, *kind).is_err());
+    }
+    assert!(HybridSet::from_iter_with_kind([1].iter().cloned(), u64::MAX, 1, HybridSetKind::Dense).is_err());
+    // more values than the universe can hold
+    assert!(HybridSet::from_vec(&[5, 3, 1]).is_err());
+    assert!(HybridSet::from_iter(0..20, 10, 20).is_err());
+    assert_eq!(HybridSet::estimate_sizes(10, 20).len(), 1);
+}
@@ -0,0 +1,48 @@
+use elias_fano_rust::*;
+mod utils;
+use utils::*;
+
+/// Check the interpolative sequence against the given values.
+fn interpolative_test_suite(values: &[u64]) {
+    let sequence = InterpolativeSequence::from_vec(values).unwrap();
+    assert_eq!(sequence.len(), values.len());
+    assert_eq!(sequence.decode(), values);
+    assert_eq!(sequence.iter().collect::<Vec<_>>(), values);
+    for (i, value) in values.iter().enumerate() {
+        assert_eq!(sequence.select(i as u64).unwrap(), *value);
+    }
+    assert!(sequence.select(values.len() as u64).is_err());
+    for value in values.iter().flat_map(|value| vec![value.saturating_sub(1), *value, value.saturating_add(1)]) {
+        let index = values.partition_point(|x| *x < value);
+        assert_eq!(sequence.next_geq(value), values.get(index).cloned(), "next_geq of {}", value);
+    }
+}
+
+/// Return the values decoded through the common trait.
+fn decode_generic<S: SortedSequence<Value = u64>>(sequence: &S) -> Vec<u64> {
+    (0..sequence.len() as u64).map(|i| sequence.select(i).unwrap()).collect()
+}
+
+#[test]
+/// Check the interpolative coding on random and clustered sequences.
+fn test_interpolative() {
+    for (size, max) in &[(0, 1), (1, 10), (2, 10), (3, 10), (100, 10), (1_000, 1_000_000), (10_000, u64::MAX)] {
+        let values = build_random_sorted_vector(*size, *max);
+        interpolative_test_suite(&values);
+
+        let mut uniques = values.clone();
+        uniques.dedup();
+        interpolative_test_suite(&uniques);
+        assert!(InterpolativeSequence::from_vec(&uniques).unwrap().is_strict());
+    }
+
+    // a dense run of values costs no bits
+    let values = (1_000..2_000).chain(u64::MAX - 10..u64::MAX).collect::<Vec<u64>>();
+    interpolative_test_suite(&values);
+    let sequence = InterpolativeSequence::from_vec(&values).unwrap();
+    let ef = EliasFano::from_vec(&values).unwrap();
+    assert!(sequence.size() < ef.size());
+    assert_eq!(decode_generic(&sequence), decode_generic(&ef));
+
+    assert!(InterpolativeSequence::from_vec(&[1, 3, 2]).is_err());
+}
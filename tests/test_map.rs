@@ -0,0 +1,59 @@
+use elias_fano_rust::*;
+mod utils;
+use utils::*;
+
+#[test]
+/// Check the map against a vector of sorted items.
+fn test_map() {
+    for (size, max) in &[(0, 1), (1, 10), (1_000, 1_500), (10_000, 1 << 40)] {
+        let mut keys = build_random_sorted_vector(*size, *max);
+        keys.dedup();
+        let values = keys.iter().map(|key| key.to_string()).collect::<Vec<_>>();
+
+        let map = EliasFanoMap::from_vec(&keys, values.clone()).unwrap();
+        let other = EliasFanoMap::from_iter(
+            keys.iter().cloned().zip(values.iter().cloned()),
+            *keys.first().unwrap_or(&0),
+            *keys.last().unwrap_or(&0),
+            keys.len(),
+        ).unwrap();
+        assert_eq!(map, other);
+        assert!(!map.keys().is_strict());
+        assert_eq!(map.len(), keys.len());
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            keys.iter().cloned().zip(values.iter()).collect::<Vec<_>>()
+        );
+
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(map.get(*key), Some(&values[i]));
+            assert_eq!(map.select(i as u64).unwrap(), (*key, &values[i]));
+            assert!(map.contains_key(*key));
+            if keys.binary_search(&(key + 1)).is_err() {
+                assert_eq!(map.get(key + 1), None);
+            }
+        }
+
+        if keys.len() > 2 {
+            let (start, end) = (keys[keys.len() / 3] + 1, keys[2 * keys.len() / 3]);
+            let truth = keys.iter().cloned().zip(values.iter())
+                .filter(|(key, _)| start <= *key && *key < end)
+                .collect::<Vec<_>>();
+            assert_eq!(map.range(start..end).collect::<Vec<_>>(), truth);
+        }
+    }
+}
+
+#[test]
+/// Check that the invalid inputs are reported.
+fn test_map_errors() {
+    assert!(EliasFanoMap::from_vec(&[1, 2, 3], vec![1, 2]).is_err());
+    assert!(EliasFanoMap::from_vec(&[1, 2, 2], vec![1, 2, 3]).is_err());
+    assert!(EliasFanoMap::from_iter(vec![(1, 1), (2, 2)].into_iter(), 1, 5, 3).is_err());
+    assert!(EliasFanoMap::from_iter(vec![(1, 1), (2, 2), (2, 3)].into_iter(), 1, 5, 3).is_err());
+
+    let mut map = EliasFanoMap::from_vec(&[1, 5], vec![1, 2]).unwrap();
+    *map.get_mut(5).unwrap() += 10;
+    assert_eq!(map.values(), &[1, 12]);
+    assert!(map.get_mut(3).is_none());
+}
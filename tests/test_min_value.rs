@@ -0,0 +1,54 @@
+use elias_fano_rust::EliasFano;
+mod utils;
+use utils::*;
+
+#[test]
+/// Check that the values far from zero are encoded relatively to the minimum.
+fn test_min_value() {
+    let offset = 1_600_000_000_u64;
+    let vector = build_random_sorted_vector(10_000, 100_000_000)
+        .into_iter().map(|x| x + offset).collect::<Vec<u64>>();
+
+    let ef = EliasFano::from_vec_auto_min(&vector).unwrap();
+    let no_offset = EliasFano::from_vec(&vector).unwrap();
+    assert_eq!(ef.get_min_value(), vector[0]);
+    assert_eq!(no_offset.get_min_value(), 0);
+    assert!(ef.size() < no_offset.size());
+
+    assert_eq!(ef.iter().collect::<Vec<_>>(), vector);
+    vector.iter().enumerate().for_each(|(i, v)| {
+        assert_eq!(*v, ef.select(i as u64).unwrap());
+        assert!(ef.contains(*v));
+        assert_eq!(ef.select(ef.unchecked_rank(*v)).unwrap(), *v);
+    });
+
+    // values before the minimum
+    assert!(!ef.contains(0));
+    assert!(!ef.contains(vector[0] - 1));
+    assert_eq!(ef.rank(vector[0] - 1), None);
+    assert_eq!(ef.unchecked_rank(0), 0);
+
+    let (start, end) = (vector[100], vector[200]);
+    let truth = vector.iter().cloned().filter(|x| (start..end).contains(x)).collect::<Vec<_>>();
+    assert_eq!(ef.iter_in_range(start..end).collect::<Vec<_>>(), truth);
+    let truth = vector.iter().cloned().filter(|x| *x < end).collect::<Vec<_>>();
+    assert_eq!(ef.iter_in_range(0..end).collect::<Vec<_>>(), truth);
+}
+
+#[test]
+/// Check the explicit minimum value constructor.
+fn test_new_with_min_value() {
+    let mut ef = EliasFano::new_with_min_value(100_u64, 1_000, 3).unwrap();
+    assert!(ef.push(99).is_err());
+    ef.push(100).unwrap();
+    ef.push(500).unwrap();
+    ef.push(1_000).unwrap();
+    assert_eq!(ef.iter().collect::<Vec<_>>(), vec![100, 500, 1_000]);
+    assert_eq!(ef.get_universe(), 1_000);
+
+    assert!(EliasFano::new_with_min_value(10_u64, 5, 3).is_err());
+
+    let mut buffer = Vec::new();
+    ef.serialize(&mut buffer).unwrap();
+    assert_eq!(EliasFano::deserialize(&mut buffer.as_slice()).unwrap(), ef);
+}
@@ -0,0 +1,60 @@
+use elias_fano_rust::*;
+mod utils;
+use utils::*;
+
+/// Return the k nearest elements by brute force, with the same tie-breaking.
+fn k_nearest_truth(vector: &[u64], value: u64, k: usize) -> Vec<(u64, u64)> {
+    let mut truth = vector.iter().cloned().enumerate()
+        .map(|(index, element)| (index as u64, element))
+        .collect::<Vec<_>>();
+    // sort by distance, then smaller values first and, among the smaller
+    // duplicates, the last one first as they are reached going backward
+    truth.sort_by_key(|(index, element)| {
+        let distance = (*element as i128 - value as i128).abs();
+        let order = match *element < value {
+            true => -(*index as i128),
+            false => *index as i128,
+        };
+        (distance, *element, order)
+    });
+    truth.truncate(k);
+    truth
+}
+
+#[test]
+/// Check nearest and k_nearest against a brute force search.
+fn test_nearest() {
+    for (size, max) in &[(1, 10), (100, 100), (1_000, 1_500), (2_000, 1_000_000)] {
+        let vector = build_random_sorted_vector(*size, *max);
+        let mut strict_vector = vector.clone();
+        strict_vector.dedup();
+        for (ef, vector) in [
+            (EliasFano::from_vec(&vector).unwrap(), &vector),
+            (EliasFano::from_vec_strict(&strict_vector).unwrap(), &strict_vector),
+        ] {
+            for value in (0..max + 10).step_by(1 + *max as usize / 300) {
+                let truth = k_nearest_truth(vector, value, 1);
+                assert_eq!(ef.nearest(value), truth.first().cloned(), "nearest to {}", value);
+                for k in &[0, 2, 7, 100, size + 1] {
+                    let truth = k_nearest_truth(vector, value, *k);
+                    assert_eq!(ef.k_nearest(value, *k), truth, "{} nearest to {}", k, value);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+/// Check nearest with a minimum value and on an empty set.
+fn test_nearest_corner_cases() {
+    let ef = EliasFano::from_vec(&[100_u32, 100, 1_000, 70_000, u32::MAX]).unwrap();
+    assert_eq!(ef.nearest(0), Some((0, 100)));
+    assert_eq!(ef.nearest(550), Some((1, 100)));
+    assert_eq!(ef.nearest(551), Some((2, 1_000)));
+    assert_eq!(ef.nearest(u32::MAX - 1), Some((4, u32::MAX)));
+    assert_eq!(ef.k_nearest(1_000, 3), vec![(2, 1_000), (1, 100), (0, 100)]);
+
+    let empty = EliasFano::<u64>::new(1_000, 10).unwrap();
+    assert_eq!(empty.nearest(500), None);
+    assert!(empty.k_nearest(500, 3).is_empty());
+}
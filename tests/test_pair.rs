@@ -0,0 +1,77 @@
+use elias_fano_rust::*;
+use rand::rngs::SmallRng;
+use rand::RngCore;
+use rand::SeedableRng;
+use rayon::prelude::*;
+mod utils;
+use utils::*;
+
+/// Build a sorted vector of random pairs.
+fn build_random_pairs(size: usize, max_first: u64, max_second: u64) -> Vec<(u64, u64)> {
+    let mut rng: SmallRng = SmallRng::from_seed(SEED);
+    let mut pairs = (0..size)
+        .map(|_| (rng.next_u64() % max_first, rng.next_u64() % max_second))
+        .collect::<Vec<_>>();
+    pairs.sort();
+    pairs
+}
+
+/// Check all the methods of the pair elias fano with the given packer.
+fn pair_test_suite<P: Packer>(pairs: &[(u64, u64)], packer: P, max_first: u64) {
+    let ef = PairEliasFano::from_vec(pairs, packer).unwrap();
+    assert_eq!(ef.len(), pairs.len());
+    assert_eq!(ef.iter().collect::<Vec<_>>(), pairs);
+    assert_eq!(ef.par_iter().collect::<Vec<_>>(), pairs);
+
+    for (i, (first, second)) in pairs.iter().enumerate() {
+        assert_eq!(ef.select(i as u64).unwrap(), (*first, *second));
+        assert!(ef.contains(*first, *second));
+        assert_eq!(
+            ef.rank(*first, *second),
+            Some(pairs.iter().position(|pair| pair == &(*first, *second)).unwrap() as u64)
+        );
+    }
+
+    for first in 0..max_first {
+        let truth = pairs.iter().cloned()
+            .filter(|(pair_first, _)| *pair_first == first)
+            .collect::<Vec<_>>();
+        assert_eq!(ef.iter_with_first(first).collect::<Vec<_>>(), truth);
+        assert_eq!(ef.count_with_first(first), truth.len() as u64);
+    }
+
+    let truth = pairs.iter().cloned()
+        .filter(|pair| (1, 3) <= *pair && *pair < (max_first / 2, 0))
+        .collect::<Vec<_>>();
+    assert_eq!(ef.iter_in_range((1, 3)..(max_first / 2, 0)).collect::<Vec<_>>(), truth);
+}
+
+#[test]
+/// Check the pair elias fano with both the packers.
+fn test_pair_elias_fano() {
+    for (size, max_first, max_second) in &[(0, 1, 1), (10, 4, 8), (1_000, 16, 1_024), (10_000, 200, 1_000)] {
+        let pairs = build_random_pairs(*size, *max_first, *max_second);
+        let bits = |max: u64| 64 - (max - 1).leading_zeros() as u64;
+        pair_test_suite(&pairs, ShiftPacker::new(bits(*max_first), bits(*max_second)).unwrap(), *max_first);
+        pair_test_suite(&pairs, MulPacker::new(*max_first, *max_second).unwrap(), *max_first);
+    }
+}
+
+#[test]
+/// Check that the packers report the invalid pairs.
+fn test_packers_errors() {
+    assert!(ShiftPacker::new(32, 32).is_err());
+    assert!(MulPacker::new(1 << 32, 1 << 32).is_err());
+
+    let packer = ShiftPacker::new(2, 3).unwrap();
+    assert_eq!(packer.universe(), 31);
+    assert_eq!(packer.unpack(packer.pack(3, 7)), (3, 7));
+    assert!(PairEliasFano::from_vec(&[(4, 0)], packer).is_err());
+    assert!(PairEliasFano::from_vec(&[(0, 8)], packer).is_err());
+
+    let packer = MulPacker::new(3, 5).unwrap();
+    assert_eq!(packer.universe(), 14);
+    assert_eq!(packer.unpack(packer.pack(2, 4)), (2, 4));
+    assert!(PairEliasFano::from_vec(&[(0, 5)], packer).is_err());
+    assert!(PairEliasFano::from_vec(&[(1, 0), (0, 1)], packer).is_err());
+}
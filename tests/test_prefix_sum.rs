@@ -0,0 +1,65 @@
+use elias_fano_rust::*;
+use rand::rngs::SmallRng;
+use rand::RngCore;
+use rand::SeedableRng;
+mod utils;
+use utils::*;
+
+/// Build a vector of random lengths, with about a quarter of empty buckets.
+fn build_random_lengths(size: usize, max: u64) -> Vec<u64> {
+    let mut rng: SmallRng = SmallRng::from_seed(SEED);
+    (0..size).map(|_| match rng.next_u64() % 4 {
+        0 => 0,
+        _ => rng.next_u64() % max,
+    }).collect()
+}
+
+#[test]
+/// Check the prefix sums against the offsets computed on a vector.
+fn test_prefix_sum() {
+    for (size, max) in &[(0, 1), (1, 10), (1_000, 2), (10_000, 100), (10_000, 1 << 30)] {
+        let lengths = build_random_lengths(*size, *max);
+        let mut offsets = vec![0_u64];
+        for length in &lengths {
+            offsets.push(offsets.last().unwrap() + length);
+        }
+        let total = *offsets.last().unwrap();
+
+        let prefix_sum = EliasFanoPrefixSum::from_vec(&lengths).unwrap();
+        assert_eq!(prefix_sum.len(), lengths.len());
+        assert_eq!(prefix_sum.total(), total);
+        assert_eq!(prefix_sum.iter().collect::<Vec<_>>(), offsets);
+        assert_eq!(prefix_sum.iter_lengths().collect::<Vec<_>>(), lengths);
+
+        for (i, length) in lengths.iter().enumerate() {
+            let i = i as u64;
+            assert_eq!(prefix_sum.get(i).unwrap(), offsets[i as usize]);
+            assert_eq!(
+                prefix_sum.get_pair(i).unwrap(),
+                (offsets[i as usize], offsets[i as usize + 1])
+            );
+            assert_eq!(prefix_sum.bucket_len(i).unwrap(), *length);
+            if *length > 0 {
+                assert_eq!(prefix_sum.find(offsets[i as usize]), Some(i));
+                assert_eq!(prefix_sum.find(offsets[i as usize + 1] - 1), Some(i));
+            }
+        }
+        assert_eq!(prefix_sum.get(lengths.len() as u64).unwrap(), total);
+        assert!(prefix_sum.get(lengths.len() as u64 + 1).is_err());
+        assert!(prefix_sum.get_pair(lengths.len() as u64).is_err());
+        assert_eq!(prefix_sum.find(total), None);
+
+        let mut buffer = Vec::new();
+        prefix_sum.serialize(&mut buffer).unwrap();
+        assert_eq!(EliasFanoPrefixSum::deserialize(&mut buffer.as_slice()).unwrap(), prefix_sum);
+    }
+}
+
+#[test]
+/// Check that the inconsistent inputs are reported.
+fn test_prefix_sum_errors() {
+    assert!(EliasFanoPrefixSum::from_iter([1, 2, 3].iter().cloned(), 5, 3).is_err());
+    assert!(EliasFanoPrefixSum::from_iter([1, 2, 3].iter().cloned(), 7, 3).is_err());
+    assert!(EliasFanoPrefixSum::from_iter([1, 2, 3].iter().cloned(), 6, 4).is_err());
+    assert!(EliasFanoPrefixSum::from_vec(&[u64::MAX, 1]).is_err());
+}
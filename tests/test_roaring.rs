@@ -0,0 +1,113 @@
+use elias_fano_rust::*;
+use rand::rngs::SmallRng;
+use rand::RngCore;
+use rand::SeedableRng;
+mod utils;
+use utils::*;
+
+/// {1, 2, 3, 65541} with two array containers, written without runs.
+const ARRAY_FIXTURE: [u8; 32] = [
+    0x3a, 0x30, 0, 0, // cookie 12346
+    2, 0, 0, 0,       // number of containers
+    0, 0, 2, 0,       // key 0, cardinality 3
+    1, 0, 0, 0,       // key 1, cardinality 1
+    24, 0, 0, 0,      // offset of the first container
+    30, 0, 0, 0,      // offset of the second container
+    1, 0, 2, 0, 3, 0, // values of the first container
+    5, 0,             // values of the second container
+];
+
+/// [10, 20) ∪ [100, 102] ∪ {65536 + 7} with a run container and an array container.
+const RUN_FIXTURE: [u8; 25] = [
+    0x3b, 0x30, 1, 0, // cookie 12347 with 2 containers
+    0b01,             // the first container is a run container
+    0, 0, 12, 0,      // key 0, cardinality 13
+    1, 0, 0, 0,       // key 1, cardinality 1
+    2, 0,             // number of runs
+    10, 0, 9, 0,      // run [10, 19]
+    100, 0, 2, 0,     // run [100, 102]
+    7, 0,             // values of the second container
+];
+
+#[test]
+/// Check the hand-built fixtures of the format.
+fn test_roaring_fixtures() {
+    let ef = EliasFano::from_roaring(&mut &ARRAY_FIXTURE[..]).unwrap();
+    assert_eq!(ef.iter().collect::<Vec<_>>(), vec![1, 2, 3, 65541]);
+    let mut buffer = Vec::new();
+    ef.to_roaring(&mut buffer).unwrap();
+    assert_eq!(buffer, ARRAY_FIXTURE);
+
+    let bitmap = SimpleSelect::from_roaring(&mut &ARRAY_FIXTURE[..]).unwrap();
+    assert_eq!(bitmap.iter().collect::<Vec<_>>(), vec![1, 2, 3, 65541]);
+    let mut buffer = Vec::new();
+    bitmap.to_roaring(&mut buffer).unwrap();
+    assert_eq!(buffer, ARRAY_FIXTURE);
+
+    let mut truth = (10..20).chain(100..103).collect::<Vec<u64>>();
+    truth.push(65536 + 7);
+    let ef = EliasFano::from_roaring(&mut &RUN_FIXTURE[..]).unwrap();
+    assert_eq!(ef.iter().collect::<Vec<_>>(), truth);
+    // the export does not use run containers
+    let mut buffer = Vec::new();
+    ef.to_roaring(&mut buffer).unwrap();
+    assert_eq!(buffer[..4], [0x3a, 0x30, 0, 0]);
+    assert_eq!(EliasFano::from_roaring(&mut buffer.as_slice()).unwrap(), ef);
+}
+
+#[test]
+/// Check the round-trip of sets with array and bitmap containers.
+fn test_roaring_round_trip() {
+    let mut rng = SmallRng::from_seed(SEED);
+    let mut values = (0..10_000).map(|_| rng.next_u64() & 0xffff_ffff).collect::<Vec<_>>();
+    values.extend((3 << 16..4 << 16).step_by(3));
+    values.sort();
+    values.dedup();
+
+    let ef = EliasFano::from_vec(&values).unwrap();
+    let mut buffer = Vec::new();
+    ef.to_roaring(&mut buffer).unwrap();
+    assert_eq!(EliasFano::from_roaring(&mut buffer.as_slice()).unwrap(), ef);
+
+    let bitmap = SimpleSelect::from_roaring(&mut buffer.as_slice()).unwrap();
+    assert_eq!(bitmap.iter().collect::<Vec<_>>(), values);
+    let mut bitmap_buffer = Vec::new();
+    bitmap.to_roaring(&mut bitmap_buffer).unwrap();
+    assert_eq!(bitmap_buffer, buffer);
+
+    // the duplicates are written once
+    let ef = EliasFano::from_vec(&[1_u64, 1, 2]).unwrap();
+    let mut buffer = Vec::new();
+    ef.to_roaring(&mut buffer).unwrap();
+    assert_eq!(EliasFano::from_roaring(&mut buffer.as_slice()).unwrap().iter().collect::<Vec<_>>(), vec![1, 2]);
+
+    let empty = EliasFano::<u64>::from_vec(&[]).unwrap();
+    let mut buffer = Vec::new();
+    empty.to_roaring(&mut buffer).unwrap();
+    assert!(EliasFano::from_roaring(&mut buffer.as_slice()).unwrap().is_empty());
+}
+
+#[test]
+/// Check that the invalid streams are rejected.
+fn test_roaring_errors() {
+    let ef = EliasFano::from_vec(&[1_u64, 1 << 32]).unwrap();
+    assert!(ef.to_roaring(&mut Vec::new()).is_err());
+
+    let mut wrong_cookie = ARRAY_FIXTURE;
+    wrong_cookie[0] = 0;
+    assert!(EliasFano::from_roaring(&mut &wrong_cookie[..]).is_err());
+
+    assert!(EliasFano::from_roaring(&mut &ARRAY_FIXTURE[..31]).is_err());
+
+    let mut unsorted = ARRAY_FIXTURE;
+    unsorted[26] = 3;
+    assert!(EliasFano::from_roaring(&mut &unsorted[..]).is_err());
+
+    let mut wrong_cardinality = RUN_FIXTURE;
+    wrong_cardinality[7] = 1;
+    assert!(EliasFano::from_roaring(&mut &wrong_cardinality[..]).is_err());
+
+    let mut overlapping_runs = RUN_FIXTURE;
+    overlapping_runs[19] = 15;
+    assert!(EliasFano::from_roaring(&mut &overlapping_runs[..]).is_err());
+}
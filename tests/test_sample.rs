@@ -0,0 +1,86 @@
+#![cfg(feature = "rand")]
+use elias_fano_rust::*;
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+mod utils;
+use utils::*;
+
+#[test]
+/// Check that the samples are roughly uniform over the positions, so that
+/// the duplicated values are weighted by their multiplicity.
+fn test_sample_uniform() {
+    let mut rng = SmallRng::from_seed(SEED);
+    let vector = [3_u64, 10, 10, 10, 1_000, 50_000, 50_001, 1 << 40];
+    let ef = EliasFano::from_vec(&vector).unwrap();
+    let mut counts = vec![0_usize; vector.len()];
+    for _ in 0..80_000 {
+        let value = ef.sample(&mut rng).unwrap();
+        counts[ef.unchecked_rank(value) as usize] += 1;
+    }
+    // the three 10 are counted on the rank of the first one
+    for (rank, count) in counts.iter().enumerate() {
+        let expected = match rank {
+            1 => 30_000,
+            2 | 3 => 0,
+            _ => 10_000,
+        };
+        assert!((*count as i64 - expected as i64).abs() < 1_000, "rank {} sampled {} times", rank, count);
+    }
+    assert_eq!(EliasFano::<u64>::new(1_000, 10).unwrap().sample(&mut rng), None);
+}
+
+#[test]
+/// Check that the samples in a range are in the range, and None when it's empty.
+fn test_sample_in_range() {
+    let mut rng = SmallRng::from_seed(SEED);
+    let vector = build_random_sorted_vector(10_000, 1_000_000);
+    let ef = EliasFano::from_vec(&vector).unwrap();
+    let bitmap = SimpleSelect::from_vec({
+        let mut words = vec![0_u64; 1_000_000 / 64 + 1];
+        for value in &vector {
+            words[*value as usize >> 6] |= 1 << (value & 63);
+        }
+        words
+    });
+    for start in (0..1_000_100).step_by(997) {
+        for width in &[0, 1, 100, 10_000] {
+            let range = start..start + width;
+            let truth = vector.iter().any(|value| range.contains(value));
+            match ef.sample_in_range(range.clone(), &mut rng) {
+                Some(value) => assert!(range.contains(&value) && ef.contains(value)),
+                None => assert!(!truth, "no sample in {:?}", range),
+            }
+            match bitmap.sample_in_range(range.clone(), &mut rng) {
+                Some(position) => assert!(range.contains(&position) && bitmap.get(position)),
+                None => assert!(!truth, "no sample in {:?}", range),
+            }
+        }
+    }
+}
+
+#[test]
+/// Check that sampling without replacement returns distinct positions.
+fn test_sample_k_without_replacement() {
+    let mut rng = SmallRng::from_seed(SEED);
+    let vector = build_random_sorted_vector(1_000, 10_000);
+    let ef = EliasFano::from_vec(&vector).unwrap();
+    for k in &[0, 1, 10, 500, 1_000] {
+        let sample = ef.sample_k_without_replacement(*k, &mut rng).unwrap();
+        assert_eq!(sample.len(), *k);
+        assert!(sample.windows(2).all(|pair| pair[0] <= pair[1]));
+        // every value is sampled at most as many times as it appears
+        for value in &sample {
+            let times = sample.iter().filter(|other| *other == value).count();
+            assert!(times <= vector.iter().filter(|other| *other == value).count());
+        }
+    }
+    assert_eq!(ef.sample_k_without_replacement(1_000, &mut rng).unwrap(), vector);
+    assert!(ef.sample_k_without_replacement(1_001, &mut rng).is_err());
+
+    let bitmap = SimpleSelect::from_vec(vec![0b1011, 0, u64::MAX]);
+    let sample = bitmap.sample_k_without_replacement(30, &mut rng).unwrap();
+    assert!(sample.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(sample.iter().all(|position| bitmap.get(*position)));
+    assert_eq!(bitmap.sample_k_without_replacement(67, &mut rng).unwrap(), bitmap.iter().collect::<Vec<_>>());
+    assert!(bitmap.sample_k_without_replacement(68, &mut rng).is_err());
+}
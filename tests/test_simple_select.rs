@@ -0,0 +1,130 @@
+use elias_fano_rust::*;
+use rand::rngs::SmallRng;
+use rand::Rng;
+use rand::SeedableRng;
+mod utils;
+use utils::*;
+
+/// Build a bitvector whose number of ones and of zeros are multiples of
+/// the quantum, so that the last index sample is at the end.
+fn build_bitvector() -> (SimpleSelect, Vec<bool>) {
+    let mut rng: SmallRng = SmallRng::from_seed(SEED);
+    let mut bits = vec![true; 4 * 1024];
+    bits.extend(vec![false; 2 * 1024]);
+    for i in (1..bits.len()).rev() {
+        bits.swap(i, rng.gen_range(0, i + 1));
+    }
+    let mut bitmap = SimpleSelect::new();
+    for bit in &bits {
+        bitmap.push(*bit);
+    }
+    (bitmap, bits)
+}
+
+#[test]
+/// Check that the checked queries return None exactly out of bounds.
+fn test_simple_select_checked() {
+    let (bitmap, bits) = build_bitvector();
+    let ones = bits.iter().enumerate().filter(|(_, bit)| **bit).map(|(i, _)| i as u64).collect::<Vec<_>>();
+    let zeros = bits.iter().enumerate().filter(|(_, bit)| !**bit).map(|(i, _)| i as u64).collect::<Vec<_>>();
+
+    for (i, bit) in bits.iter().enumerate() {
+        assert_eq!(bitmap.try_get(i as u64), Some(*bit));
+        assert_eq!(bitmap.get(i as u64), *bit);
+        assert_eq!(bitmap.unchecked_get(i as u64), *bit);
+    }
+    for (i, position) in ones.iter().enumerate() {
+        assert_eq!(bitmap.try_select1(i as u64), Some(*position));
+        assert_eq!(bitmap.select1(i as u64), *position);
+        assert_eq!(bitmap.unchecked_select1(i as u64), *position);
+    }
+    for (i, position) in zeros.iter().enumerate() {
+        assert_eq!(bitmap.try_select0(i as u64), Some(*position));
+        assert_eq!(bitmap.select0(i as u64), *position);
+        assert_eq!(bitmap.unchecked_select0(i as u64), *position);
+    }
+
+    assert_eq!(bitmap.try_get(bitmap.len()), None);
+    assert_eq!(bitmap.try_get(u64::MAX), None);
+    assert_eq!(bitmap.try_select1(bitmap.count_ones()), None);
+    assert_eq!(bitmap.try_select0(bitmap.count_zeros()), None);
+    assert_eq!(bitmap.rank1(u64::MAX), bitmap.count_ones());
+    assert_eq!(bitmap.rank0(u64::MAX), bitmap.count_zeros());
+
+    let empty = SimpleSelect::new();
+    assert_eq!(empty.try_get(0), None);
+    assert_eq!(empty.try_select1(0), None);
+    assert_eq!(empty.try_select0(0), None);
+    assert_eq!(empty.rank1(10), 0);
+}
+
+#[test]
+#[should_panic(expected = "out of bound")]
+fn test_simple_select_select1_out_of_bounds() {
+    let (bitmap, _) = build_bitvector();
+    bitmap.select1(bitmap.count_ones());
+}
+
+#[test]
+#[should_panic(expected = "out of bound")]
+fn test_simple_select_select0_out_of_bounds() {
+    let (bitmap, _) = build_bitvector();
+    bitmap.select0(bitmap.count_zeros());
+}
+
+#[test]
+#[should_panic(expected = "out of bound")]
+fn test_simple_select_get_out_of_bounds() {
+    let (bitmap, _) = build_bitvector();
+    bitmap.get(bitmap.len());
+}
+
+#[test]
+/// Check the double-ended iterators against the forward one, alternating
+/// the two ends, also on ranges inside a single word and up to the length.
+fn test_simple_select_double_ended() {
+    let (bitmap, _) = build_bitvector();
+    let single_word = SimpleSelect::from_vec(vec![0b1011_0110]);
+    let mut rng: SmallRng = SmallRng::from_seed(SEED);
+    for bitmap in &[bitmap, single_word, SimpleSelect::new()] {
+        let mut ranges = vec![0..bitmap.len(), 0..u64::MAX, 3..5, 1..64, 64..128, 70..70];
+        for _ in 0..200 {
+            let (start, end) = (rng.gen_range(0, bitmap.len() + 2), rng.gen_range(0, bitmap.len() + 2));
+            ranges.push(start.min(end)..start.max(end));
+        }
+        for range in ranges {
+            let truth = bitmap.iter().filter(|position| range.contains(position)).collect::<Vec<_>>();
+            let iter = bitmap.iter_in_range_double_ended(range.clone());
+            assert_eq!(iter.len(), truth.len(), "len of {:?}", range);
+            assert_eq!(iter.collect::<Vec<_>>(), truth, "forward on {:?}", range);
+            assert_eq!(
+                bitmap.iter_in_range_double_ended(range.clone()).rev().collect::<Vec<_>>(),
+                truth.iter().rev().cloned().collect::<Vec<_>>(),
+                "backward on {:?}", range
+            );
+
+            let mut iter = bitmap.iter_in_range_double_ended(range.clone());
+            let (mut start, mut end) = (0, truth.len());
+            loop {
+                let forward = rng.gen_bool(0.5);
+                let test = match forward {
+                    true => iter.next(),
+                    false => iter.next_back(),
+                };
+                if start == end {
+                    assert_eq!(test, None, "alternating on {:?}", range);
+                    break;
+                }
+                if forward {
+                    assert_eq!(test, Some(truth[start]), "alternating on {:?}", range);
+                    start += 1;
+                } else {
+                    end -= 1;
+                    assert_eq!(test, Some(truth[end]), "alternating on {:?}", range);
+                }
+                assert_eq!(iter.len(), end - start);
+            }
+        }
+        assert_eq!(bitmap.iter_double_ended().collect::<Vec<_>>(), bitmap.iter().collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,66 @@
+use elias_fano_rust::*;
+mod utils;
+use utils::*;
+
+/// Build a sorted vector without duplicates.
+fn build_random_strict_vector(size: usize, max: u64) -> Vec<u64> {
+    let mut vector = build_random_sorted_vector(size, max);
+    vector.dedup();
+    vector
+}
+
+#[test]
+/// Check that the strict mode returns the same results of the normal one.
+fn test_strict() {
+    for (size, max) in &[(1, 10), (100, 100), (1_000, 1_500), (10_000, 1_000_000), (10_000, 1 << 50)] {
+        let vector = build_random_strict_vector(*size, *max);
+        let ef = EliasFano::from_vec_strict(&vector).unwrap();
+        let truth = EliasFano::from_vec(&vector).unwrap();
+
+        assert!(ef.is_strict());
+        assert!(ef.size() <= truth.size());
+        assert_eq!(ef.iter().collect::<Vec<_>>(), vector);
+        assert_eq!(ef.iter_select().collect::<Vec<_>>(), vector);
+
+        vector.iter().enumerate().for_each(|(i, v)| {
+            assert_eq!(*v, ef.select(i as u64).unwrap());
+            assert_eq!(ef.rank(*v), Some(i as u64));
+            assert!(ef.contains(*v));
+        });
+
+        // check the values not in the set
+        let last = *vector.last().unwrap();
+        for value in (0..last + 10).step_by(1 + (last as usize / 5_000)) {
+            assert_eq!(ef.unchecked_rank(value), truth.unchecked_rank(value), "rank of {}", value);
+            assert_eq!(ef.contains(value), truth.contains(value), "contains of {}", value);
+            assert_eq!(ef.rank(value), truth.rank(value), "rank of {}", value);
+        }
+
+        let (start, end) = (vector[vector.len() / 3], vector[2 * vector.len() / 3] + 1);
+        assert_eq!(
+            ef.iter_in_range(start..end).collect::<Vec<_>>(),
+            truth.iter_in_range(start..end).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            ef.iter_in_range(0..last + 1).collect::<Vec<_>>(),
+            vector
+        );
+
+        let mut buffer = Vec::new();
+        ef.serialize(&mut buffer).unwrap();
+        assert_eq!(EliasFano::deserialize(&mut buffer.as_slice()).unwrap(), ef);
+    }
+}
+
+#[test]
+/// Check that a dense strict set is encoded with no low bits.
+fn test_strict_dense() {
+    let vector = (1_000..2_000_u32).collect::<Vec<_>>();
+    let ef = EliasFano::from_vec_strict(&vector).unwrap();
+    assert_eq!(ef.low_bit_count(), 0);
+    assert_eq!(ef.iter().collect::<Vec<_>>(), vector);
+    assert_eq!(ef.unchecked_rank(1_500), 500);
+
+    assert!(EliasFano::new_strict(0_u32, 5, 10).is_err());
+    assert!(EliasFano::from_vec_strict(&[1_u32, 2, 2]).is_err());
+}
@@ -0,0 +1,132 @@
+use elias_fano_rust::*;
+use elias_fano_rust::traits::*;
+mod utils;
+use utils::*;
+
+/// Check any bitvector against the sorted positions of its ones.
+fn check_bitvector<B: Rank + Select + SelectZero>(bitvector: &B, ones: &[u64]) {
+    let mut zeros = Vec::new();
+    let mut ones_iter = ones.iter().peekable();
+    for position in 0..bitvector.len() {
+        // the number of ones in [0, position)
+        assert_eq!(bitvector.rank1(position), position - zeros.len() as u64, "rank1 of {}", position);
+        let is_one = ones_iter.peek() == Some(&&position);
+        if is_one {
+            ones_iter.next();
+        } else {
+            zeros.push(position);
+        }
+        assert_eq!(bitvector.get(position), is_one, "get of {}", position);
+    }
+    for (i, position) in ones.iter().enumerate() {
+        assert_eq!(bitvector.select1(i as u64), *position);
+        assert_eq!(bitvector.rank1(*position), i as u64);
+    }
+    for (i, position) in zeros.iter().enumerate() {
+        assert_eq!(bitvector.select0(i as u64), *position);
+        assert_eq!(bitvector.rank0(*position), i as u64);
+    }
+}
+
+/// Check any sorted sequence against its values.
+fn check_sorted_sequence<S: SortedSequence<Value = u64>>(sequence: &S, values: &[u64]) {
+    assert_eq!(sequence.len(), values.len());
+    assert_eq!(sequence.decode(), values);
+    let last = *values.last().unwrap_or(&0);
+    for value in 0..last + 3 {
+        let truth = values.iter().cloned().find(|x| *x >= value);
+        assert_eq!(sequence.next_geq(value), truth, "next_geq of {}", value);
+    }
+}
+
+#[test]
+/// Check that the traits are consistent between the implementations.
+fn test_traits() {
+    for (size, max) in &[(1, 10), (100, 1_000), (2_000, 2_500)] {
+        let mut values = build_random_sorted_vector(*size, *max);
+        values.dedup();
+
+        let ef = EliasFano::from_vec(&values).unwrap();
+        check_bitvector(&ef, &values);
+        check_sorted_sequence(&ef, &values);
+        check_sorted_sequence(&InterpolativeSequence::from_vec(&values).unwrap(), &values);
+
+        let mut simple_select = SimpleSelect::new();
+        for position in 0..=*values.last().unwrap() {
+            simple_select.push(values.binary_search(&position).is_ok());
+        }
+        check_bitvector(&simple_select, &values);
+    }
+}
+
+#[test]
+/// Check the bitvector view of the EliasFano with duplicated values,
+/// in strict mode and with other value types.
+fn test_traits_elias_fano() {
+    let values = build_random_sorted_vector(1_000, 700);
+    let mut uniques = values.clone();
+    uniques.dedup();
+    assert!(uniques.len() < values.len());
+
+    let ef = EliasFano::from_vec(&values).unwrap();
+    let mut zeros = 0;
+    for position in 0..BitVector::len(&ef) {
+        let is_one = uniques.binary_search(&position).is_ok();
+        assert_eq!(BitVector::get(&ef, position), is_one, "get of {}", position);
+        assert_eq!(ef.rank1(position), values.iter().filter(|x| **x < position).count() as u64);
+        if !is_one {
+            assert_eq!(ef.select0(zeros), position, "select0 of {}", zeros);
+            zeros += 1;
+        }
+    }
+    for (i, value) in values.iter().enumerate() {
+        assert_eq!(ef.select1(i as u64), *value);
+    }
+
+    check_bitvector(&EliasFano::from_vec_strict(&uniques).unwrap(), &uniques);
+    let values_u32: Vec<u32> = uniques.iter().map(|x| *x as u32).collect();
+    check_bitvector(&EliasFano::from_vec(&values_u32).unwrap(), &uniques);
+    let values_u8: Vec<u8> = uniques.iter().filter(|x| **x < 256).map(|x| *x as u8).collect();
+    let uniques_u8: Vec<u64> = values_u8.iter().map(|x| *x as u64).collect();
+    check_bitvector(&EliasFano::from_vec(&values_u8).unwrap(), &uniques_u8);
+}
+
+/// Return the positions of the ones of a random bitvector and its bits.
+#[cfg(any(feature = "fid", feature = "succinct"))]
+fn build_bits() -> (Vec<u64>, Vec<bool>) {
+    let mut ones = build_random_sorted_vector(500, 2_000);
+    ones.dedup();
+    let mut bits = vec![false; *ones.last().unwrap() as usize + 1];
+    for one in &ones {
+        bits[*one as usize] = true;
+    }
+    (ones, bits)
+}
+
+#[test]
+#[cfg(feature = "fid")]
+/// Check the generic code against fid, imported next to the crate's traits.
+fn test_traits_fid() {
+    use fid::{BitVector as FidBitVector, FID};
+    let (ones, bits) = build_bits();
+    let mut bitvector = FidBitVector::new();
+    for bit in &bits {
+        bitvector.push(*bit);
+    }
+    assert_eq!(FID::len(&bitvector), bits.len() as u64);
+    check_bitvector(&bitvector, &ones);
+}
+
+#[test]
+#[cfg(feature = "succinct")]
+/// Check the generic code against succinct.
+fn test_traits_succinct() {
+    use succinct::bit_vec::BitVecPush;
+    let (ones, bits) = build_bits();
+    let mut bitvector: succinct::BitVector<u64> = succinct::BitVector::new();
+    for bit in &bits {
+        bitvector.push_bit(*bit);
+    }
+    let bitvector = succinct::BinSearchSelect::new(succinct::Rank9::new(bitvector));
+    check_bitvector(&bitvector, &ones);
+}
@@ -0,0 +1,226 @@
+use elias_fano_rust::*;
+mod utils;
+use utils::*;
+
+#[test]
+/// Check that the structures built by the crate are valid.
+fn test_validate_valid() {
+    let values = build_random_sorted_vector(10_000, 1_000_000);
+    let ef = EliasFano::from_vec(&values).unwrap();
+    assert_eq!(ef.validate(), ValidationReport::default());
+    assert!(ef.validate().into_result().is_ok());
+
+    let mut strict_values = values.clone();
+    strict_values.dedup();
+    assert!(EliasFano::from_vec_strict(&strict_values).unwrap().validate().is_valid());
+
+    let values_u32 = values.iter().map(|x| *x as u32).collect::<Vec<_>>();
+    assert!(EliasFano::from_vec(&values_u32).unwrap().validate().is_valid());
+
+    let builder = ConcurrentEliasFanoBuilder::new(values.len() as u64, 1_000_000).unwrap();
+    for (index, value) in values.iter().enumerate() {
+        builder.set(index as u64, *value);
+    }
+    assert!(builder.build().unwrap().validate().is_valid());
+
+    let mut partial = EliasFano::new(1_000_u64, 10).unwrap();
+    assert!(partial.validate().is_valid());
+    for value in [1, 5, 5, 900] {
+        partial.push(value).unwrap();
+    }
+    assert!(partial.validate().is_valid());
+
+    let mut buffer = Vec::new();
+    ef.serialize(&mut buffer).unwrap();
+    assert!(EliasFano::<u64>::deserialize(&mut buffer.as_slice()).unwrap().validate().is_valid());
+
+    let mut bitmap = SimpleSelect::new();
+    for value in values.iter().take(5_000) {
+        bitmap.push(value % 3 == 0);
+    }
+    assert!(bitmap.validate().is_valid());
+}
+
+/// Return the parts of the high-bits of the EliasFano.
+fn high_bits_parts(ef: &EliasFano<u64>) -> (Vec<u64>, Vec<u64>, Vec<u64>, u64, u64, u64) {
+    let high_bits = ef.high_bits();
+    (
+        high_bits.high_bits_words().to_vec(),
+        high_bits.high_bits_index_zeros().to_vec(),
+        high_bits.high_bits_index_ones().to_vec(),
+        high_bits.count_ones(),
+        high_bits.count_zeros(),
+        high_bits.len(),
+    )
+}
+
+/// Build an EliasFano with the given parts and the other ones of `ef`.
+fn from_parts(
+    ef: &EliasFano<u64>,
+    low_bits: Vec<u64>,
+    (words, index_zeros, index_ones, ones, zeros, len): (Vec<u64>, Vec<u64>, Vec<u64>, u64, u64, u64),
+    universe: u64,
+    number_of_elements: usize,
+    low_bit_count: u64,
+) -> EliasFano<u64> {
+    unsafe {
+        let high_bits = SimpleSelect::from_raw_parts(words, index_zeros, index_ones, ones, zeros, len);
+        EliasFano::from_raw_parts(
+            low_bits, high_bits, universe, ef.get_min_value(),
+            ef.is_strict(), number_of_elements, low_bit_count,
+        )
+    }
+}
+
+#[test]
+/// Check that each corruption is reported as the right invariant.
+fn test_validate_corrupted() {
+    let values = build_random_sorted_vector(5_000, 100_000);
+    let ef = EliasFano::from_vec(&values).unwrap();
+    let low_bits = ef.low_bits().to_vec();
+    let (universe, capacity, bits) = (ef.get_universe(), ef.capacity(), ef.low_bit_count());
+
+    let copy = from_parts(&ef, low_bits.clone(), high_bits_parts(&ef), universe, capacity, bits);
+    assert!(copy.validate().is_valid());
+    assert_eq!(copy, ef);
+
+    let mut parts = high_bits_parts(&ef);
+    parts.0[3] ^= 1 << 7;
+    let report = from_parts(&ef, low_bits.clone(), parts, universe, capacity, bits).validate();
+    assert!(report.violates(Invariant::Popcount));
+    assert!(report.into_result().is_err());
+
+    let mut parts = high_bits_parts(&ef);
+    parts.2[1] += 1;
+    let report = from_parts(&ef, low_bits.clone(), parts, universe, capacity, bits).validate();
+    assert!(report.violates(Invariant::IndexOnes));
+
+    let mut parts = high_bits_parts(&ef);
+    parts.1.pop();
+    let report = from_parts(&ef, low_bits.clone(), parts, universe, capacity, bits).validate();
+    assert!(report.violates(Invariant::IndexZeros));
+
+    let mut parts = high_bits_parts(&ef);
+    parts.5 += 1;
+    let report = from_parts(&ef, low_bits.clone(), parts, universe, capacity, bits).validate();
+    assert!(report.violates(Invariant::Length));
+
+    let report = from_parts(&ef, low_bits.clone(), high_bits_parts(&ef), universe, capacity - 1, bits).validate();
+    assert!(report.violates(Invariant::ElementCount));
+
+    let report = from_parts(&ef, low_bits.clone(), high_bits_parts(&ef), universe, capacity, 65).validate();
+    assert!(report.violates(Invariant::LowBits));
+
+    let report = from_parts(&ef, low_bits[..2].to_vec(), high_bits_parts(&ef), universe, capacity, bits).validate();
+    assert!(report.violates(Invariant::LowBits));
+
+    let mut corrupted_low_bits = low_bits.clone();
+    *corrupted_low_bits.last_mut().unwrap() = 1;
+    let report = from_parts(&ef, corrupted_low_bits, high_bits_parts(&ef), universe, capacity, bits).validate();
+    assert!(report.violates(Invariant::Padding));
+
+    // swap the low-bits of two different values with the same high-bits
+    let read = |words: &[u64], i: u64| {
+        let position = i * bits;
+        let word = (words[(position >> 6) as usize] as u128) | (words[(position >> 6) as usize + 1] as u128) << 64;
+        (word >> (position & 63)) as u64 & ((1 << bits) - 1)
+    };
+    let mut lows = (0..values.len() as u64).map(|i| read(&low_bits, i)).collect::<Vec<_>>();
+    let index = (0..values.len() - 1).find(|i| {
+        let (a, b) = (values[*i] - values[0], values[*i + 1] - values[0]);
+        a >> bits == b >> bits && a != b
+    }).unwrap();
+    lows.swap(index, index + 1);
+    let mut corrupted_low_bits = vec![0; low_bits.len()];
+    for (i, low) in lows.iter().enumerate() {
+        let position = i as u64 * bits;
+        corrupted_low_bits[(position >> 6) as usize] |= low << (position & 63);
+        if (position & 63) + bits > 64 {
+            corrupted_low_bits[(position >> 6) as usize + 1] |= low >> (64 - (position & 63));
+        }
+    }
+    let report = from_parts(&ef, corrupted_low_bits, high_bits_parts(&ef), universe, capacity, bits).validate();
+    assert!(report.violates(Invariant::Monotonicity));
+    assert_eq!(report.violations.len(), 1);
+
+    let report = from_parts(&ef, low_bits, high_bits_parts(&ef), values[values.len() / 2], capacity, bits).validate();
+    assert!(report.violates(Invariant::Range));
+}
+
+#[test]
+/// Check that the checksum detects the corruptions of the serialized data.
+fn test_checksum() {
+    let values = build_random_sorted_vector(1_000, 100_000);
+    let ef = EliasFano::from_vec(&values).unwrap();
+
+    let mut buffer = Vec::new();
+    ef.serialize_with_checksum(&mut buffer).unwrap();
+    assert_eq!(buffer[..8], CHECKSUM_SERIALIZATION_MAGIC.to_le_bytes());
+    assert_eq!(EliasFano::<u64>::deserialize(&mut buffer.as_slice()).unwrap(), ef);
+
+    let mut plain_buffer = Vec::new();
+    ef.serialize(&mut plain_buffer).unwrap();
+    assert_eq!(buffer.len(), plain_buffer.len() + 8);
+    assert_eq!(buffer[8..buffer.len() - 8], plain_buffer[8..]);
+
+    // flip the lowest low-bit of the first value, which keeps the structure
+    // valid so it is not detected without the checksum
+    assert!(values[0] < values[1]);
+    let position = 104;
+    buffer[position] ^= 1;
+    plain_buffer[position] ^= 1;
+    assert!(EliasFano::<u64>::deserialize(&mut plain_buffer.as_slice()).is_ok());
+    assert!(EliasFano::<u64>::deserialize(&mut buffer.as_slice()).is_err());
+    buffer[position] ^= 1;
+
+    // corrupted checksum
+    let last = buffer.len() - 1;
+    buffer[last] ^= 1;
+    assert!(EliasFano::<u64>::deserialize(&mut buffer.as_slice()).is_err());
+
+    // missing checksum
+    assert!(EliasFano::<u64>::deserialize(&mut &buffer[..last - 7]).is_err());
+}
+
+#[test]
+/// Check that the deserialization rejects the streams whose parts break the invariants.
+fn test_deserialize_corrupted() {
+    let values = build_random_sorted_vector(1_000, 100_000);
+    let ef = EliasFano::from_vec(&values).unwrap();
+    let (universe, capacity, bits) = (ef.get_universe(), ef.capacity(), ef.low_bit_count());
+
+    // no low-bits
+    let mut buffer = Vec::new();
+    from_parts(&ef, Vec::new(), high_bits_parts(&ef), universe, capacity, bits).serialize(&mut buffer).unwrap();
+    assert!(EliasFano::<u64>::deserialize(&mut buffer.as_slice()).is_err());
+
+    // a wrong index of the ones
+    let mut parts = high_bits_parts(&ef);
+    parts.2[0] += 1;
+    let mut buffer = Vec::new();
+    from_parts(&ef, ef.low_bits().to_vec(), parts, universe, capacity, bits).serialize(&mut buffer).unwrap();
+    assert!(EliasFano::<u64>::deserialize(&mut buffer.as_slice()).is_err());
+
+    let mut buffer = Vec::new();
+    ef.high_bits().serialize(&mut buffer).unwrap();
+    assert_eq!(&SimpleSelect::deserialize(&mut buffer.as_slice()).unwrap(), ef.high_bits());
+    // the first word of the high-bits follows the three fields and its length
+    buffer[32] ^= 1;
+    assert!(SimpleSelect::deserialize(&mut buffer.as_slice()).is_err());
+}
+
+#[test]
+/// Check that a stream with more elements than its capacity is rejected
+/// instead of reading past the low-bits.
+fn test_deserialize_over_capacity() {
+    let values = build_random_sorted_vector(1_000, 100_000);
+    let ef = EliasFano::from_vec(&values).unwrap();
+    let (universe, bits) = (ef.get_universe(), ef.low_bit_count());
+
+    let mut buffer = Vec::new();
+    from_parts(&ef, vec![0; 3], high_bits_parts(&ef), universe, 1, bits).serialize(&mut buffer).unwrap();
+    // the last value follows the magic number and the other fields of the header,
+    // whose values take 16 bytes, a valid one passes the checks of the header
+    buffer[80..88].copy_from_slice(&values[values.len() - 1].to_le_bytes());
+    assert!(EliasFano::<u64>::deserialize(&mut buffer.as_slice()).is_err());
+}
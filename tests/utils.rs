@@ -1,3 +1,6 @@
+// each test includes this module but uses only some of the helpers
+#![allow(dead_code)]
+
 use elias_fano_rust::EliasFano;
 use rand::rngs::SmallRng;
 use rand::RngCore;